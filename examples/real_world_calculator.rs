@@ -176,6 +176,7 @@ mod arithmetic_tests {
 #[cfg(test)]
 mod error_tests {
     use super::*;
+    use rust_test_harness::assertions::assert_approx_eq;
     
     test_case!(test_division_by_zero, |_ctx| {
         let mut calc = Calculator::new();
@@ -207,7 +208,7 @@ mod error_tests {
         
         let result = calc.add(0.1, 0.2);
         // Use approx comparison for floating point
-        assert!((result - 0.3).abs() < 1e-10);
+        assert_approx_eq(result, 0.3, 1e-10)?;
         
         Ok(())
     });
@@ -0,0 +1,68 @@
+//! Command-line flags for test binaries that call `run_tests_with_config` themselves
+//! (`harness = false`), so `cargo test --test api -- --filter db --jobs 4` works like a
+//! test runner.
+//!
+//! Flags override what `TestConfig::default()` read from the `TEST_*` environment variables:
+//!
+//! | Flag | Sets |
+//! |------|------|
+//! | `--filter TEXT` | `filter` |
+//! | `--skip-tags a,b` | `skip_tags` |
+//! | `--only-tags a,b` | `only_tags` |
+//! | `--jobs N` | `max_concurrency` |
+//! | `--shuffle-seed N` | `shuffle_seed` |
+//! | `--html PATH` | `html_report` |
+//! | `--no-hooks` | `skip_hooks` |
+//! | `--fail-fast` | `fail_fast` |
+//! | `--list` | `list_only` |
+//!
+//! Flags taking a value accept it as the next argument or after `=` (`--jobs=4`).
+
+use crate::TestConfig;
+
+/// Apply `args` (without the program name) to `config`. Unknown flags, missing values and
+/// values that don't parse are errors, so a typo can't silently run the whole suite.
+pub fn apply<I, S>(config: &mut TestConfig, args: I) -> Result<(), String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = || inline_value.clone().or_else(|| args.next()).ok_or_else(|| format!("{} needs a value", flag));
+        match flag.as_str() {
+            "--filter" => config.filter = Some(value()?),
+            "--skip-tags" => config.skip_tags = tag_list(&value()?),
+            "--only-tags" => config.only_tags = tag_list(&value()?),
+            "--jobs" => {
+                let jobs = value()?;
+                match jobs.parse::<usize>() {
+                    Ok(jobs) if jobs > 0 => config.max_concurrency = Some(jobs),
+                    _ => return Err(format!("--jobs needs a positive number, got {:?}", jobs)),
+                }
+            }
+            "--shuffle-seed" => {
+                let seed = value()?;
+                config.shuffle_seed = Some(seed.parse().map_err(|_| format!("--shuffle-seed needs a number, got {:?}", seed))?);
+            }
+            "--html" => config.html_report = Some(value()?),
+            "--no-hooks" | "--fail-fast" | "--list" if inline_value.is_some() => {
+                return Err(format!("{} doesn't take a value", flag));
+            }
+            "--no-hooks" => config.skip_hooks = Some(true),
+            "--fail-fast" => config.fail_fast = true,
+            "--list" => config.list_only = true,
+            _ => return Err(format!("unknown argument {:?}", flag)),
+        }
+    }
+    Ok(())
+}
+
+// Comma-separated, like the TEST_*_TAGS variables
+fn tag_list(value: &str) -> Vec<String> {
+    value.split(',').map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect()
+}
@@ -0,0 +1,167 @@
+//! Assertions for comparing response bodies and numeric results, and for polling
+//! eventually-consistent state.
+//!
+//! They return a `TestResult` instead of panicking, so a mismatch is reported as an ordinary
+//! test failure with a message pointing at what differs: `assert_json_eq(&body, expected)?;`
+
+use std::time::{Duration, Instant};
+
+use crate::{TestError, TestResult};
+
+/// Compare two texts line by line, reporting the first line that differs
+pub fn assert_text_eq(actual: &str, expected: &str) -> TestResult {
+    let mut actual_lines = actual.lines();
+    let mut expected_lines = expected.lines();
+    for line in 1.. {
+        match (actual_lines.next(), expected_lines.next()) {
+            (None, None) => break,
+            (Some(a), Some(e)) if a == e => {}
+            (a, e) => {
+                return Err(TestError::Message(format!(
+                    "texts differ at line {}: expected {}, got {}",
+                    line,
+                    e.map_or("end of text".to_string(), |e| format!("{:?}", e)),
+                    a.map_or("end of text".to_string(), |a| format!("{:?}", a)),
+                )));
+            }
+        }
+    }
+    // Same lines; only line endings or a trailing newline can differ
+    if actual != expected {
+        return Err(TestError::Message("texts differ only in line endings or a trailing newline".to_string()));
+    }
+    Ok(())
+}
+
+/// Tolerance for `assert_approx_eq`. Values match when they differ by at most `abs`, or by at
+/// most `rel` times the larger magnitude of the two, whichever allows more. A bare `f64`
+/// converts to an absolute tolerance.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ApproxEq {
+    pub abs: f64,
+    pub rel: f64,
+}
+
+impl ApproxEq {
+    /// Allow a fixed difference, for values near zero
+    pub fn abs(abs: f64) -> Self {
+        Self { abs, rel: 0.0 }
+    }
+    
+    /// Allow a difference proportional to the values, for values of any magnitude
+    pub fn rel(rel: f64) -> Self {
+        Self { abs: 0.0, rel }
+    }
+    
+    /// The largest difference allowed between `actual` and `expected`
+    pub fn allowed(&self, actual: f64, expected: f64) -> f64 {
+        self.abs.max(self.rel * actual.abs().max(expected.abs()))
+    }
+}
+
+impl From<f64> for ApproxEq {
+    fn from(abs: f64) -> Self {
+        Self::abs(abs)
+    }
+}
+
+/// Compare floating-point results within a tolerance: `assert_approx_eq(total, 0.3, 1e-9)?;`
+/// or `assert_approx_eq(total, 0.3, ApproxEq::rel(1e-6))?;`. NaN never matches; infinities
+/// only match themselves.
+pub fn assert_approx_eq(actual: f64, expected: f64, tolerance: impl Into<ApproxEq>) -> TestResult {
+    let tolerance = tolerance.into();
+    if actual == expected {
+        return Ok(());
+    }
+    let delta = (actual - expected).abs();
+    let allowed = tolerance.allowed(actual, expected);
+    // A relative tolerance scaled by an infinity would accept anything
+    if actual.is_finite() && expected.is_finite() && delta <= allowed {
+        return Ok(());
+    }
+    Err(TestError::Message(format!(
+        "expected {} ± {}, got {} (delta {}, abs {}, rel {})",
+        expected, allowed, actual, delta, tolerance.abs, tolerance.rel,
+    )))
+}
+
+/// Compare two JSON documents structurally: key order and whitespace don't matter.
+/// On mismatch the error lists every differing path, e.g. `$.user.name: expected "a", got "b"`.
+#[cfg(feature = "serde_json")]
+pub fn assert_json_eq(actual: &str, expected: &str) -> TestResult {
+    let actual: serde_json::Value = serde_json::from_str(actual)
+        .map_err(|e| TestError::Message(format!("actual is not valid JSON: {}", e)))?;
+    let expected: serde_json::Value = serde_json::from_str(expected)
+        .map_err(|e| TestError::Message(format!("expected is not valid JSON: {}", e)))?;
+    
+    let mut differences = Vec::new();
+    json_diff("$", &actual, &expected, &mut differences);
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(TestError::Message(format!("JSON differs:\n{}", differences.join("\n"))))
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn json_diff(path: &str, actual: &serde_json::Value, expected: &serde_json::Value, differences: &mut Vec<String>) {
+    use serde_json::Value;
+    
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, expected_value) in expected {
+                let child = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => json_diff(&child, actual_value, expected_value, differences),
+                    None => differences.push(format!("{}: expected {}, got nothing", child, expected_value)),
+                }
+            }
+            for (key, actual_value) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(format!("{}.{}: unexpected {}", path, key, actual_value));
+                }
+            }
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            if actual.len() != expected.len() {
+                differences.push(format!("{}: expected {} elements, got {}", path, expected.len(), actual.len()));
+            }
+            for (i, (actual_value, expected_value)) in actual.iter().zip(expected).enumerate() {
+                json_diff(&format!("{}[{}]", path, i), actual_value, expected_value, differences);
+            }
+        }
+        (actual, expected) if actual != expected => {
+            differences.push(format!("{}: expected {}, got {}", path, expected, actual));
+        }
+        _ => {}
+    }
+}
+
+/// Retry `check` every `interval` until it passes or `timeout` runs out, for state that
+/// settles eventually: `eventually(Duration::from_secs(5), Duration::from_millis(100), || {
+/// assert_text_eq(&fetch_status(), "ready") })?;`. `check` runs at least once, and once more
+/// at the deadline. On timeout the error gives the attempt count and `check`'s last error.
+/// A skip (`ctx.skip`) is returned straight away rather than retried.
+pub fn eventually<F>(timeout: Duration, interval: Duration, mut check: F) -> TestResult
+where
+    F: FnMut() -> TestResult,
+{
+    let start = Instant::now();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match check() {
+            Ok(()) => return Ok(()),
+            Err(skip @ TestError::Skipped(_)) => return Err(skip),
+            Err(e) => e,
+        };
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return Err(TestError::Message(format!(
+                "condition not met within {:?} after {} attempt{}: {}",
+                timeout, attempts, if attempts == 1 { "" } else { "s" }, error,
+            )));
+        }
+        std::thread::sleep(interval.min(timeout - elapsed));
+    }
+}
@@ -0,0 +1,146 @@
+//! Minimal HTTP/1.1 client for probing services in tests.
+//!
+//! Plain HTTP only (no TLS, no redirects, no keep-alive): it is meant for talking to
+//! containers and local servers on `localhost`, not as a general-purpose client.
+
+use crate::TestError;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// A fully read HTTP response
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// First header with this name (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the status is 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Issue a GET request to an `http://` URL. `timeout` bounds the whole exchange.
+pub fn get(url: &str, timeout: Duration) -> Result<HttpResponse, TestError> {
+    let deadline = Instant::now() + timeout;
+    let (host, port, path) = parse_url(url)?;
+
+    let addr = (host.as_str(), port).to_socket_addrs()
+        .map_err(|e| TestError::Message(format!("GET {}: cannot resolve {}: {}", url, host, e)))?
+        .next()
+        .ok_or_else(|| TestError::Message(format!("GET {}: no address for {}", url, host)))?;
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)
+        .map_err(|e| TestError::Message(format!("GET {}: connect failed: {}", url, e)))?;
+
+    let request = format!("GET {} HTTP/1.1\r\nHost: {}:{}\r\nConnection: close\r\nAccept: */*\r\n\r\n", path, host, port);
+    stream.set_write_timeout(Some(timeout)).and_then(|_| stream.write_all(request.as_bytes()))
+        .map_err(|e| TestError::Message(format!("GET {}: sending request failed: {}", url, e)))?;
+
+    // Read until the server closes the connection or the deadline passes
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(TestError::Timeout(timeout));
+        }
+        stream.set_read_timeout(Some(remaining))
+            .map_err(|e| TestError::Message(format!("GET {}: {}", url, e)))?;
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => raw.extend_from_slice(&buf[..n]),
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                return Err(TestError::Timeout(timeout));
+            }
+            Err(e) => return Err(TestError::Message(format!("GET {}: reading response failed: {}", url, e))),
+        }
+    }
+
+    parse_response(&raw).map_err(|e| TestError::Message(format!("GET {}: {}", url, e)))
+}
+
+/// Split `http://host[:port][/path]` into its parts
+fn parse_url(url: &str) -> Result<(String, u16, String), TestError> {
+    let rest = url.strip_prefix("http://")
+        .ok_or_else(|| TestError::Message(format!("unsupported URL (only http:// is supported): {}", url)))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port.parse()
+                .map_err(|_| TestError::Message(format!("invalid port in URL: {}", url)))?;
+            (host, port)
+        }
+        None => (authority, 80),
+    };
+    if host.is_empty() {
+        return Err(TestError::Message(format!("missing host in URL: {}", url)));
+    }
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+fn parse_response(raw: &[u8]) -> Result<HttpResponse, String> {
+    let header_end = raw.windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("malformed response: no end of headers")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let mut lines = head.split("\r\n");
+
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line.split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("malformed status line: {}", status_line))?;
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let mut response = HttpResponse { status, headers, body: String::new() };
+    let body = &raw[header_end + 4..];
+    let body = if response.header("transfer-encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked")) {
+        decode_chunked(body)?
+    } else {
+        match response.header("content-length").and_then(|len| len.parse::<usize>().ok()) {
+            Some(len) => body[..len.min(body.len())].to_vec(),
+            None => body.to_vec(),
+        }
+    };
+    response.body = String::from_utf8_lossy(&body).into_owned();
+    Ok(response)
+}
+
+fn decode_chunked(mut data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = data.windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or("malformed chunked body")?;
+        let size_line = String::from_utf8_lossy(&data[..line_end]);
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| format!("malformed chunk size: {}", size_line))?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Ok(body);
+        }
+        if data.len() < size {
+            return Err("truncated chunked body".to_string());
+        }
+        body.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or_default();
+    }
+}
@@ -0,0 +1,37 @@
+//! SplitMix64, the generator behind `shuffle_with_seed` and `TestRng`.
+//!
+//! The state starts at the seed itself rather than a hash of it, so a sequence depends only
+//! on the seed: no `DefaultHasher`, whose output may change between Rust releases. An order
+//! found with a seed reproduces anywhere the same seed and input are used.
+
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    
+    /// Uniform value in `0..bound` (`bound` > 0), without the bias of `next_u64() % bound`.
+    /// Lemire's multiply-and-reject method.
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        let mut product = u128::from(self.next_u64()) * u128::from(bound);
+        if (product as u64) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (product as u64) < threshold {
+                product = u128::from(self.next_u64()) * u128::from(bound);
+            }
+        }
+        (product >> 64) as u64
+    }
+}
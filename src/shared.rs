@@ -0,0 +1,141 @@
+//! Shared state for tests and hooks that accumulate across a run.
+//!
+//! `SharedCounter` and `SharedLog` are cheap handles: clone one into each closure and
+//! read it after the run. `shared` hands out process-global state by key, for when
+//! threading a handle into every closure is more wiring than it's worth. `Fixtures` carries
+//! values a `before_all` hook sets up into every test of that run.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use once_cell::sync::OnceCell;
+
+// Process-global state handed out by `shared`, keyed by name
+static SHARED_STATE: OnceCell<Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>>> = OnceCell::new();
+
+/// Thread-safe counter; clones share the same count
+#[derive(Debug, Clone, Default)]
+pub struct SharedCounter {
+    count: Arc<AtomicUsize>,
+}
+
+impl SharedCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one and return the new count
+    pub fn increment(&self) -> usize {
+        self.add(1)
+    }
+
+    /// Add `n` and return the new count
+    pub fn add(&self, n: usize) -> usize {
+        self.count.fetch_add(n, Ordering::SeqCst) + n
+    }
+
+    /// Current count
+    pub fn get(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+/// Thread-safe list of messages, e.g. to record the order hooks ran in; clones share the same list
+#[derive(Debug, Clone, Default)]
+pub struct SharedLog {
+    entries: Arc<Mutex<Vec<String>>>,
+}
+
+impl SharedLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an entry
+    pub fn push(&self, entry: impl Into<String>) {
+        self.lock().push(entry.into());
+    }
+
+    /// All entries so far, oldest first
+    pub fn entries(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    // A panicking test can poison the lock; the entries it pushed are still worth reading
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        self.entries.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Values shared with every test of a run, one per type: `before_all` stores them with
+/// `ctx.set_fixture(value)` and tests, including parallel and timed ones, read them with
+/// `ctx.fixture::<T>()`. Clones share the same values.
+#[derive(Clone, Default)]
+pub struct Fixtures {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Fixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `value`, replacing any earlier fixture of the same type
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// The fixture of type `T`, if one was stored
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.values.get(&TypeId::of::<T>()).and_then(|value| Arc::clone(value).downcast::<T>().ok())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Add `other`'s fixtures, replacing ours of the same type
+    pub(crate) fn extend(&mut self, other: Fixtures) {
+        self.values.extend(other.values);
+    }
+}
+
+impl std::fmt::Debug for Fixtures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Fixtures({} values)", self.values.len())
+    }
+}
+
+/// Process-global state stored under `key`, created with `T::default()` on first use.
+/// Every caller asking for the same key gets the same `Arc`, across tests and runs.
+///
+/// Panics if `key` was first used with a different type.
+pub fn shared<T: Default + Send + 'static>(key: &str) -> Arc<Mutex<T>> {
+    let mut state = SHARED_STATE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let entry = state
+        .entry(key.to_string())
+        .or_insert_with(|| Arc::new(Mutex::new(T::default())));
+    Arc::clone(entry)
+        .downcast::<Mutex<T>>()
+        .unwrap_or_else(|_| panic!("shared state '{}' was created with a different type", key))
+}
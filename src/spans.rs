@@ -0,0 +1,93 @@
+//! `tracing` spans for test execution (feature `tracing`).
+//!
+//! Each run gets a `test_suite` span; every test gets a `test` span inside it, and every hook
+//! a `hook` span inside the suite or the test it wraps. Without the feature these are no-ops.
+
+use crate::TestCase;
+
+#[cfg(feature = "tracing")]
+pub(crate) type Entered = tracing::span::EnteredSpan;
+
+/// Stand-in for an entered span when the `tracing` feature is off
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Entered;
+
+#[cfg(feature = "tracing")]
+pub(crate) type Parent = tracing::Span;
+
+/// Stand-in for a span handed to worker threads when the `tracing` feature is off
+#[cfg(not(feature = "tracing"))]
+#[derive(Clone)]
+pub(crate) struct Parent;
+
+/// Enter the span covering a whole run
+#[cfg(feature = "tracing")]
+pub(crate) fn suite(test_count: usize) -> Entered {
+    tracing::info_span!("test_suite", test_count).entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn suite(_test_count: usize) -> Entered {
+    Entered
+}
+
+/// Enter the span for one test; its status and duration are filled in by `record_outcome`
+#[cfg(feature = "tracing")]
+pub(crate) fn test(name: &str) -> Entered {
+    tracing::info_span!(
+        "test",
+        test.name = name,
+        test.status = tracing::field::Empty,
+        test.duration_ms = tracing::field::Empty,
+    ).entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn test(_name: &str) -> Entered {
+    Entered
+}
+
+/// Enter the span for one hook, e.g. `hook("before_each")`
+#[cfg(feature = "tracing")]
+pub(crate) fn hook(kind: &'static str) -> Entered {
+    tracing::info_span!("hook", hook.kind = kind).entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn hook(_kind: &'static str) -> Entered {
+    Entered
+}
+
+/// Record how a finished test ended on its span
+#[cfg(feature = "tracing")]
+pub(crate) fn record_outcome(span: &Entered, test: &TestCase) {
+    span.record("test.status", crate::status_name(&test.status));
+    if let Some(duration) = test.duration {
+        span.record("test.duration_ms", duration.as_millis() as u64);
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn record_outcome(_span: &Entered, _test: &TestCase) {}
+
+/// The span a worker thread should run its tests inside (the caller's current span)
+#[cfg(feature = "tracing")]
+pub(crate) fn current() -> Parent {
+    tracing::Span::current()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn current() -> Parent {
+    Parent
+}
+
+/// Enter `parent` on a worker thread so the spans created there nest under it
+#[cfg(feature = "tracing")]
+pub(crate) fn enter(parent: &Parent) -> Entered {
+    parent.clone().entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn enter(_parent: &Parent) -> Entered {
+    Entered
+}
@@ -0,0 +1,59 @@
+//! Tests for per-test allocation tracking
+//!
+//! Tracking needs `CountingAllocator` as the global allocator, which is per binary,
+//! so these live in their own test binary.
+
+use rust_test_harness::{test, run_tests_with_config, allocated_bytes, CountingAllocator, TestConfig};
+use std::fs;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn test_allocated_bytes_counts_the_current_thread() {
+    let before = allocated_bytes();
+    let buffer = vec![0u8; 4096];
+    assert!(allocated_bytes() - before >= 4096);
+    drop(buffer);
+    
+    // Freeing memory doesn't lower the running total, and arithmetic doesn't allocate
+    let before = allocated_bytes();
+    let sum: u64 = (0..1000u64).sum();
+    assert_eq!(sum, 499_500);
+    assert_eq!(allocated_bytes() - before, 0);
+}
+
+#[test]
+fn test_track_allocations_reports_bytes_per_test() {
+    println!("🧪 Testing allocation tracking...");
+    
+    test("allocates_a_megabyte", |_| {
+        let buffer = vec![1u8; 1024 * 1024];
+        assert_eq!(buffer.len(), 1024 * 1024);
+        Ok(())
+    });
+    test("allocates_nothing", |_| {
+        assert_eq!(2 + 2, 4);
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        track_allocations: true,
+        max_concurrency: Some(1),
+        html_report: Some("test_allocation_report.html".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_allocation_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    assert!(html_content.contains("Allocated"));
+    assert!(html_content.contains("0 bytes"), "A test that doesn't allocate reports 0 bytes");
+    assert!(html_content.contains(&format!("{} bytes", 1024 * 1024)));
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+    
+    println!("✅ Allocation tracking test passed");
+}
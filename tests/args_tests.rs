@@ -0,0 +1,118 @@
+//! Tests for command-line flag parsing and `list_only` dry runs
+
+use rust_test_harness::{args, before_all, run_tests_collect, test, test_with_tags, RunOutcome, SharedCounter, TestConfig, TestStatus};
+
+/// A config with every field the flags touch at its unset value, so env vars can't leak in
+fn blank_config() -> TestConfig {
+    TestConfig {
+        filter: None,
+        skip_tags: Vec::new(),
+        only_tags: Vec::new(),
+        max_concurrency: None,
+        shuffle_seed: None,
+        html_report: None,
+        skip_hooks: None,
+        fail_fast: false,
+        list_only: false,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_args_override_config() {
+    let mut config = blank_config();
+    args::apply(&mut config, [
+        "--filter", "db", "--skip-tags", "slow, flaky", "--only-tags=integration",
+        "--jobs", "4", "--shuffle-seed=42", "--html", "report.html", "--no-hooks", "--fail-fast", "--list",
+    ]).unwrap();
+
+    assert_eq!(config.filter.as_deref(), Some("db"));
+    assert_eq!(config.skip_tags, vec!["slow", "flaky"]);
+    assert_eq!(config.only_tags, vec!["integration"]);
+    assert_eq!(config.max_concurrency, Some(4));
+    assert_eq!(config.shuffle_seed, Some(42));
+    assert_eq!(config.html_report.as_deref(), Some("report.html"));
+    assert_eq!(config.skip_hooks, Some(true));
+    assert!(config.fail_fast);
+    assert!(config.list_only);
+
+    // No flags leave the config alone
+    let mut config = blank_config();
+    args::apply(&mut config, Vec::<String>::new()).unwrap();
+    assert_eq!(config.filter, None);
+    assert!(!config.list_only);
+}
+
+#[test]
+fn test_args_reject_bad_input() {
+    let error = |flags: &[&str]| args::apply(&mut blank_config(), flags.iter().copied()).unwrap_err();
+
+    assert_eq!(error(&["--filtr", "db"]), "unknown argument \"--filtr\"");
+    assert_eq!(error(&["db"]), "unknown argument \"db\"");
+    assert_eq!(error(&["--filter"]), "--filter needs a value");
+    assert_eq!(error(&["--jobs", "0"]), "--jobs needs a positive number, got \"0\"");
+    assert_eq!(error(&["--jobs=many"]), "--jobs needs a positive number, got \"many\"");
+    assert_eq!(error(&["--shuffle-seed", "-1"]), "--shuffle-seed needs a number, got \"-1\"");
+    assert_eq!(error(&["--list=yes"]), "--list doesn't take a value");
+}
+
+#[test]
+fn test_list_only_runs_nothing() {
+    let ran = SharedCounter::new();
+    let (in_hook, in_test, in_tagged) = (ran.clone(), ran.clone(), ran.clone());
+    before_all(move |_| {
+        in_hook.increment();
+        Ok(())
+    });
+    test("listed_plain", move |_| {
+        in_test.increment();
+        Ok(())
+    });
+    test_with_tags("listed_tagged", vec!["db", "slow"], move |_| {
+        in_tagged.increment();
+        Ok(())
+    });
+
+    let report = run_tests_collect(TestConfig { list_only: true, ..blank_config() });
+    assert_eq!(report.outcome, RunOutcome::Listed);
+    assert_eq!(report.outcome.to_exit_code(), 0);
+    assert_eq!(ran.get(), 0, "listing must not run tests or hooks");
+    assert_eq!(report.total, 2);
+    assert!(report.results.iter().all(|(_, status, _)| *status == TestStatus::Pending));
+}
+
+#[test]
+fn test_list_only_applies_filters() {
+    use rust_test_harness::test_with_timeout;
+    use std::time::Duration;
+
+    let ran = SharedCounter::new();
+    for name in ["db_orders", "db_refunds", "api_health"] {
+        let ran = ran.clone();
+        test(name, move |_| {
+            ran.increment();
+            Ok(())
+        });
+    }
+    test_with_timeout("db_migrations", Duration::from_secs(30), |_| Ok(()));
+    test_with_tags("db_slow_report", vec!["slow"], |_| Ok(()));
+
+    let config = TestConfig {
+        filter: Some("db".to_string()),
+        skip_tags: vec!["slow".to_string()],
+        shuffle_seed: Some(7),
+        list_only: true,
+        ..blank_config()
+    };
+    let report = run_tests_collect(config);
+    assert_eq!(report.outcome, RunOutcome::Listed);
+    assert_eq!(ran.get(), 0);
+
+    let status = |name: &str| report.results.iter().find(|(n, _, _)| n == name).unwrap().1.clone();
+    for name in ["db_orders", "db_refunds", "db_migrations"] {
+        assert_eq!(status(name), TestStatus::Pending, "{} is selected", name);
+    }
+    assert_eq!(status("api_health"), TestStatus::Skipped);
+    assert_eq!(status("db_slow_report"), TestStatus::Skipped);
+    assert_eq!(report.skipped, 2);
+}
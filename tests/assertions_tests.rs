@@ -0,0 +1,92 @@
+//! Tests for the body and numeric comparison assertions and polling
+
+use std::time::Duration;
+
+use rust_test_harness::assertions::{assert_approx_eq, assert_text_eq, eventually, ApproxEq};
+use rust_test_harness::TestError;
+
+#[test]
+fn test_assert_text_eq_points_at_the_first_difference() {
+    assert!(assert_text_eq("a\nb\nc", "a\nb\nc").is_ok());
+    
+    let error = assert_text_eq("a\nB\nc", "a\nb\nc").unwrap_err();
+    assert_eq!(error, TestError::Message("texts differ at line 2: expected \"b\", got \"B\"".to_string()));
+    
+    let error = assert_text_eq("a", "a\nb").unwrap_err();
+    assert_eq!(error, TestError::Message("texts differ at line 2: expected \"b\", got end of text".to_string()));
+    
+    assert!(assert_text_eq("a\n", "a").is_err(), "A trailing newline still counts as a difference");
+}
+
+#[test]
+fn test_assert_approx_eq_tolerances() {
+    assert!(assert_approx_eq(0.1 + 0.2, 0.3, 1e-12).is_ok());
+    assert!(assert_approx_eq(1e9 + 1.0, 1e9, ApproxEq::rel(1e-6)).is_ok());
+    assert!(assert_approx_eq(1e9 + 1.0, 1e9, 0.5).is_err(), "An absolute tolerance doesn't scale");
+    assert!(assert_approx_eq(1e-12, 0.0, ApproxEq { abs: 1e-9, rel: 1e-6 }).is_ok(), "Near zero the absolute tolerance applies");
+    
+    let error = assert_approx_eq(1.5, 1.0, 0.25).unwrap_err();
+    assert_eq!(error, TestError::Message("expected 1 ± 0.25, got 1.5 (delta 0.5, abs 0.25, rel 0)".to_string()));
+    
+    assert!(assert_approx_eq(f64::NAN, f64::NAN, 1.0).is_err());
+    assert!(assert_approx_eq(f64::INFINITY, f64::INFINITY, 0.0).is_ok());
+    assert!(assert_approx_eq(f64::INFINITY, 1.0, ApproxEq::rel(0.5)).is_err());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_assert_json_eq_ignores_formatting_and_key_order() {
+    use rust_test_harness::assertions::assert_json_eq;
+    
+    let actual = r#"{"user": {"name": "a", "roles": ["admin"]}, "id": 7}"#;
+    let expected = r#"{
+        "id": 7,
+        "user": { "roles": ["admin"], "name": "a" }
+    }"#;
+    assert!(assert_json_eq(actual, expected).is_ok());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_assert_json_eq_reports_field_paths() {
+    use rust_test_harness::assertions::assert_json_eq;
+    
+    let actual = r#"{"user": {"name": "b", "roles": ["admin", "ops"], "extra": true}}"#;
+    let expected = r#"{"user": {"name": "a", "roles": ["admin"], "email": null}}"#;
+    let message = match assert_json_eq(actual, expected) {
+        Err(TestError::Message(message)) => message,
+        other => panic!("expected a mismatch, got {:?}", other),
+    };
+    assert!(message.contains("$.user.name: expected \"a\", got \"b\""), "{}", message);
+    assert!(message.contains("$.user.roles: expected 1 elements, got 2"), "{}", message);
+    assert!(message.contains("$.user.email: expected null, got nothing"), "{}", message);
+    assert!(message.contains("$.user.extra: unexpected true"), "{}", message);
+    
+    assert!(assert_json_eq("{not json", "{}").is_err());
+}
+
+#[test]
+fn test_eventually_polls_until_the_check_passes() {
+    let mut calls = 0;
+    let result = eventually(Duration::from_secs(5), Duration::from_millis(1), || {
+        calls += 1;
+        if calls < 3 { Err("not yet".into()) } else { Ok(()) }
+    });
+    assert!(result.is_ok());
+    assert_eq!(calls, 3, "Stops polling at the first success");
+    
+    let error = eventually(Duration::from_millis(30), Duration::from_millis(5), || {
+        assert_text_eq("pending", "ready")
+    }).unwrap_err().to_string();
+    assert!(error.starts_with("condition not met within 30ms after "), "{}", error);
+    assert!(error.ends_with(r#"attempts: texts differ at line 1: expected "ready", got "pending""#), "{}", error);
+    
+    // Skips aren't retried
+    let mut calls = 0;
+    let result = eventually(Duration::from_secs(5), Duration::from_millis(1), || {
+        calls += 1;
+        Err(TestError::Skipped("no backend".to_string()))
+    });
+    assert_eq!(result, Err(TestError::Skipped("no backend".to_string())));
+    assert_eq!(calls, 1);
+}
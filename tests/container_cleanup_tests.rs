@@ -0,0 +1,29 @@
+//! Tests for the order in which registered containers are cleaned up
+//!
+//! The container registry is process-wide, so these live in their own test binary.
+
+use rust_test_harness::{cleanup_all_containers_with, get_container_registry, register_container_for_cleanup};
+
+#[test]
+fn test_cleanup_stops_containers_in_reverse_registration_order() {
+    println!("🧪 Testing LIFO container cleanup...");
+    
+    register_container_for_cleanup("cleanup_db");
+    register_container_for_cleanup("cleanup_cache");
+    register_container_for_cleanup("cleanup_app");
+    
+    // Record stop calls instead of talking to Docker; a failing stop must not end the cleanup
+    let mut stopped = Vec::new();
+    cleanup_all_containers_with(|container_id| {
+        stopped.push(container_id.to_string());
+        if container_id == "cleanup_cache" {
+            return Err("simulated stop failure".into());
+        }
+        Ok(())
+    });
+    
+    assert_eq!(stopped, vec!["cleanup_app", "cleanup_cache", "cleanup_db"]);
+    assert!(get_container_registry().lock().unwrap().is_empty(), "Cleanup drains the registry");
+    
+    println!("✅ LIFO container cleanup test passed");
+}
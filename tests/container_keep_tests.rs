@@ -0,0 +1,32 @@
+//! Tests for keeping the containers of failed tests alive for inspection
+//!
+//! The container registry is process-wide, so these live in their own test binary.
+
+use rust_test_harness::{test, run_tests_with_config, container_owner, get_container_registry, register_container_for_cleanup, TestConfig};
+
+#[test]
+fn test_failed_test_containers_are_kept() {
+    println!("🧪 Testing keep_containers_on_failure...");
+    
+    test("keeps_failing_container", |_| {
+        register_container_for_cleanup("keep_failing_container");
+        Err("database assertion failed".into())
+    });
+    test("keeps_passing_container", |_| {
+        register_container_for_cleanup("keep_passing_container");
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        keep_containers_on_failure: true,
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    // The failing test's container was left alone; the passing test's went through cleanup
+    assert_eq!(container_owner("keep_failing_container"), Some("keeps_failing_container".to_string()));
+    assert_eq!(container_owner("keep_passing_container"), None);
+    assert!(get_container_registry().lock().unwrap().is_empty(), "Kept containers leave the registry so later runs don't stop them");
+    
+    println!("✅ keep_containers_on_failure test passed");
+}
@@ -0,0 +1,35 @@
+//! Tests for failing the run on leaked containers
+//!
+//! The container registry is process-wide, so these live in their own test binary.
+
+use rust_test_harness::{test, run_tests_with_config, register_container_for_cleanup, TestConfig};
+
+#[test]
+fn test_fail_on_container_leak() {
+    println!("🧪 Testing fail_on_container_leak...");
+    
+    let config = || TestConfig {
+        fail_on_container_leak: true,
+        ..Default::default()
+    };
+    
+    // No containers, no leak (and an unreachable Docker daemon isn't one either)
+    test("starts_no_containers", |_| Ok(()));
+    assert_eq!(run_tests_with_config(config()), 0);
+    
+    // One that is left for the final cleanup is
+    test("forgets_its_container", |_| {
+        register_container_for_cleanup("leak_check_forgotten_container");
+        Ok(())
+    });
+    assert_eq!(run_tests_with_config(config()), 1, "Passing tests still fail the run when a container leaked");
+    
+    // Without the flag the leak is cleaned up silently as before
+    test("forgets_its_container_again", |_| {
+        register_container_for_cleanup("leak_check_forgotten_again_container");
+        Ok(())
+    });
+    assert_eq!(run_tests_with_config(TestConfig::default()), 0);
+    
+    println!("✅ fail_on_container_leak test passed");
+}
@@ -0,0 +1,41 @@
+//! Tests for attributing registered containers to the tests that started them
+//!
+//! The container registry is process-wide, so these live in their own test binary.
+
+use rust_test_harness::{test, run_tests_with_config, container_owner, register_container_for_cleanup, TestConfig};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_registered_containers_are_attributed_to_the_running_test() {
+    println!("🧪 Testing container ownership tracking...");
+    
+    // Outside a test there is no owner to record
+    register_container_for_cleanup("tracked_outside_container");
+    assert_eq!(container_owner("tracked_outside_container"), None);
+    
+    let owners = Arc::new(Mutex::new(Vec::new()));
+    for name in ["starts_database", "starts_cache"] {
+        let owners = Arc::clone(&owners);
+        test(name, move |_| {
+            let container_id = format!("tracked_{}_container", name);
+            register_container_for_cleanup(&container_id);
+            owners.lock().unwrap().push(container_owner(&container_id));
+            Ok(())
+        });
+    }
+    
+    let config = TestConfig {
+        track_containers: true,
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0, "Leaked containers are reported, not failed");
+    
+    let mut owners = owners.lock().unwrap().clone();
+    owners.sort();
+    assert_eq!(owners, vec![Some("starts_cache".to_string()), Some("starts_database".to_string())]);
+    
+    // End-of-run cleanup forgets the containers it stopped
+    assert_eq!(container_owner("tracked_starts_database_container"), None);
+    
+    println!("✅ Container ownership tracking test passed");
+}
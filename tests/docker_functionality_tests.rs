@@ -8,9 +8,11 @@
 //! 5. Mixed port configurations (manual + auto)
 
 use rust_test_harness::{
-    ContainerConfig, ContainerInfo, 
-    get_container_registry, register_container_for_cleanup
+    ComposeStack, ContainerConfig, ContainerInfo, ContainerPool, DaemonInfo, Fault, PollStrategy, assert_container_responds,
+    TestConfig, TestError, create_network, get_container_registry, register_container_for_cleanup, remove_network, remove_volume,
+    after_each, before_each, run_tests_with_config, test
 };
+use std::io::{Read, Write};
 use std::time::Duration;
 
 #[test]
@@ -83,6 +85,7 @@ fn test_container_info_creation_and_clone() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     // Test clone
@@ -117,6 +120,7 @@ fn test_container_info_primary_url() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     let primary_url = container_info.primary_url();
@@ -130,6 +134,7 @@ fn test_container_info_primary_url() {
         urls: vec![],
         port_mappings: vec![],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     let primary_url = container_info.primary_url();
@@ -152,6 +157,7 @@ fn test_container_info_url_for_port() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     // Test existing ports
@@ -182,6 +188,7 @@ fn test_container_info_host_port_for() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     // Test existing ports
@@ -198,6 +205,42 @@ fn test_container_info_host_port_for() {
     println!("✅ ContainerInfo host_port_for test passed");
 }
 
+#[test]
+fn test_container_info_export_env() {
+    println!("🧪 Testing ContainerInfo export_env method...");
+    
+    let container_info = ContainerInfo {
+        container_id: "test".to_string(),
+        image: "test".to_string(),
+        name: None,
+        urls: vec!["localhost:15432".to_string(), "localhost:19187".to_string()],
+        port_mappings: vec![(15432, 5432), (19187, 9187)],
+        auto_cleanup: true,
+        labels: Default::default(),
+    };
+    
+    let vars = container_info.export_env("pg-main");
+    let expected = [
+        ("PG_MAIN_HOST", "localhost"),
+        ("PG_MAIN_PORT_5432", "15432"),
+        ("PG_MAIN_PORT_9187", "19187"),
+        ("PG_MAIN_URL", "localhost:15432"),
+    ];
+    assert_eq!(vars, expected.map(|(k, v)| (k.to_string(), v.to_string())).to_vec());
+    
+    // The variables reach a subprocess through the context helper
+    let ctx = rust_test_harness::TestContext::new();
+    let mut command = std::process::Command::new("env");
+    ctx.with_container_env(&mut command, &container_info, "DB");
+    let injected: Vec<_> = command.get_envs()
+        .map(|(k, v)| (k.to_string_lossy().into_owned(), v.map(|v| v.to_string_lossy().into_owned())))
+        .collect();
+    assert!(injected.contains(&("DB_PORT_5432".to_string(), Some("15432".to_string()))));
+    assert!(injected.contains(&("DB_URL".to_string(), Some("localhost:15432".to_string()))));
+    
+    println!("✅ ContainerInfo export_env test passed");
+}
+
 #[test]
 fn test_container_info_ports_summary() {
     println!("🧪 Testing ContainerInfo ports_summary method...");
@@ -213,6 +256,7 @@ fn test_container_info_ports_summary() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     let summary = container_info.ports_summary();
@@ -226,6 +270,7 @@ fn test_container_info_ports_summary() {
         urls: vec![],
         port_mappings: vec![],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     let summary = container_info.ports_summary();
@@ -239,6 +284,7 @@ fn test_container_info_ports_summary() {
         urls: vec!["http://localhost:8080".to_string()],
         port_mappings: vec![(8080, 80)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     let summary = container_info.ports_summary();
@@ -458,6 +504,7 @@ fn test_container_info_field_access() {
         urls: vec!["http://localhost:8080".to_string()],
         port_mappings: vec![(8080, 80)],
         auto_cleanup: false,
+        labels: Default::default(),
     };
     
     // Test all fields are accessible
@@ -476,6 +523,7 @@ fn test_container_info_field_access() {
         urls: vec![],
         port_mappings: vec![],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     assert_eq!(container_info.name, None);
@@ -526,6 +574,7 @@ fn test_container_info_methods_edge_cases() {
         urls: vec![],
         port_mappings: vec![],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     assert_eq!(container_info.primary_url(), None);
@@ -541,6 +590,7 @@ fn test_container_info_methods_edge_cases() {
         urls: vec!["http://localhost:8080".to_string()],
         port_mappings: vec![(8080, 80)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     assert_eq!(container_info.primary_url(), Some("http://localhost:8080"));
@@ -605,6 +655,7 @@ fn test_container_info_real_world_scenarios() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     // Test web service methods
@@ -623,6 +674,7 @@ fn test_container_info_real_world_scenarios() {
         urls: vec!["postgresql://localhost:5432".to_string()],
         port_mappings: vec![(5432, 5432)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     // Test database methods
@@ -643,6 +695,7 @@ fn test_container_info_real_world_scenarios() {
         ],
         port_mappings: vec![(8080, 80), (9090, 9090), (9091, 9091)],
         auto_cleanup: false,
+        labels: Default::default(),
     };
     
     // Test API service methods
@@ -687,6 +740,7 @@ fn test_complete_container_workflow() {
         ],
         port_mappings: vec![(8080, 80), (8443, 443)],
         auto_cleanup: true,
+        labels: Default::default(),
     };
     
     // 4. Test all ContainerInfo functionality
@@ -789,4 +843,543 @@ fn test_mixed_port_configuration_validation() {
     // Manual port 8080 should not interfere with auto-ports 443 and 9090
     
     println!("✅ Mixed port configuration validation test passed");
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_assert_container_responds_probes_mapped_port() {
+    println!("🧪 Testing assert_container_responds readiness probe...");
+    
+    // A local listener stands in for a started container's published port
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let host_port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        let _connections: Vec<_> = listener.incoming().collect();
+    });
+    
+    let container_info = ContainerInfo {
+        container_id: "probe_test".to_string(),
+        image: "test".to_string(),
+        name: None,
+        urls: vec![format!("http://localhost:{}", host_port)],
+        port_mappings: vec![(host_port, 80)],
+        auto_cleanup: false,
+        labels: Default::default(),
+    };
+    
+    assert!(assert_container_responds(&container_info, 80, Duration::from_secs(2)).is_ok());
+    
+    // An unmapped container port fails immediately with a descriptive error
+    let err = assert_container_responds(&container_info, 443, Duration::from_secs(2)).unwrap_err();
+    assert!(err.to_string().contains("no host port mapped for container port 443"));
+    
+    println!("✅ assert_container_responds test passed");
+}
+
+#[test]
+fn test_assert_container_responds_times_out_on_closed_port() {
+    println!("🧪 Testing assert_container_responds timeout error...");
+    
+    // Grab a free port and release it so nothing is listening there
+    let host_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    
+    let container_info = ContainerInfo {
+        container_id: "closed_port_test".to_string(),
+        image: "test".to_string(),
+        name: None,
+        urls: Vec::new(),
+        port_mappings: vec![(host_port, 5432)],
+        auto_cleanup: false,
+        labels: Default::default(),
+    };
+    
+    let err = assert_container_responds(&container_info, 5432, Duration::from_millis(300)).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("did not respond on port 5432"), "unexpected error: {}", message);
+    assert!(message.contains(&format!("host port {}", host_port)), "unexpected error: {}", message);
+    
+    println!("✅ assert_container_responds timeout test passed");
+}
+
+#[test]
+fn test_container_name_gets_run_unique_suffix() {
+    println!("🧪 Testing run-scoped container naming...");
+    
+    let container = ContainerConfig::new("postgres:13-alpine").name("test_postgres");
+    let first = container.resolve_name(0).unwrap();
+    let second = container.resolve_name(1).unwrap();
+    
+    // Human-readable prefix is kept, but every start gets its own name
+    assert!(first.starts_with("test_postgres-"), "unexpected name: {}", first);
+    assert!(second.starts_with("test_postgres-"), "unexpected name: {}", second);
+    assert_ne!(first, second);
+    assert_eq!(container.resolve_name(0).unwrap(), first, "resolving a name doesn't use it up");
+    
+    // exact_name() opts out of the suffix
+    let exact = ContainerConfig::new("postgres:13-alpine").name("fixed_postgres").exact_name();
+    assert!(exact.exact_name);
+    assert_eq!(exact.resolve_name(0), Some("fixed_postgres".to_string()));
+    
+    // Unnamed containers are left to Docker
+    assert_eq!(ContainerConfig::new("redis:alpine").resolve_name(0), None);
+    
+    println!("✅ Run-scoped container naming test passed");
+}
+
+#[test]
+fn test_container_pool_checkout_blocks_until_checkin() {
+    println!("🧪 Testing ContainerPool checkout/checkin...");
+    
+    let pooled = |id: &str| ContainerInfo {
+        container_id: id.to_string(),
+        image: "postgres:13-alpine".to_string(),
+        name: None,
+        urls: Vec::new(),
+        port_mappings: Vec::new(),
+        auto_cleanup: false,
+        labels: Default::default(),
+    };
+    let pool = std::sync::Arc::new(ContainerPool::from_containers(vec![pooled("pool_a"), pooled("pool_b")]));
+    assert_eq!(pool.size(), 2);
+    
+    let first = pool.checkout().unwrap();
+    let second = pool.checkout().unwrap();
+    assert_ne!(first.container_id, second.container_id);
+    assert_eq!(pool.idle(), 0);
+    
+    // A third checkout waits until a container comes back
+    let waiter = {
+        let pool = std::sync::Arc::clone(&pool);
+        std::thread::spawn(move || pool.checkout().unwrap().container_id)
+    };
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!waiter.is_finished(), "checkout should block while the pool is exhausted");
+    
+    let returned = first.container_id.clone();
+    pool.checkin(first).unwrap();
+    assert_eq!(waiter.join().unwrap(), returned);
+    
+    pool.checkin(second).unwrap();
+    assert_eq!(pool.idle(), 1);
+    
+    println!("✅ ContainerPool checkout/checkin test passed");
+}
+
+#[test]
+fn test_container_pool_size_follows_max_concurrency() {
+    let config = rust_test_harness::TestConfig {
+        max_concurrency: Some(3),
+        ..Default::default()
+    };
+    assert_eq!(ContainerPool::size_for(&config), 3);
+    
+    // An empty pool fails checkouts instead of blocking forever
+    let empty = ContainerPool::from_containers(Vec::new());
+    assert!(empty.checkout().is_err());
+}
+
+#[test]
+fn test_container_labels_identify_the_run() {
+    println!("🧪 Testing container labels...");
+    
+    let container = ContainerConfig::new("postgres:13-alpine")
+        .label("team", "payments")
+        .label("harness.run_id", "overridden");
+    assert_eq!(container.labels.get("team"), Some(&"payments".to_string()));
+    
+    let labels = ContainerConfig::new("postgres:13-alpine").label("team", "payments").resolve_labels();
+    assert_eq!(labels.get("harness.run_id").map(String::as_str), Some(rust_test_harness::process_run_id()));
+    assert_eq!(labels.get("team").map(String::as_str), Some("payments"));
+    // Outside a running test there is no test name to record
+    assert!(!labels.contains_key("harness.test_name"));
+    
+    // User labels win over the built-in ones
+    assert_eq!(container.resolve_labels().get("harness.run_id").map(String::as_str), Some("overridden"));
+    
+    println!("✅ Container labels test passed");
+}
+
+#[test]
+fn test_fault_injection_commands() {
+    println!("🧪 Testing network fault commands...");
+    
+    let container = ContainerConfig::new("alpine:latest").cap_add("NET_ADMIN");
+    assert_eq!(container.cap_add, vec!["NET_ADMIN".to_string()]);
+    
+    assert_eq!(
+        Fault::Latency(Duration::from_millis(250)).commands(),
+        vec![vec!["tc", "qdisc", "replace", "dev", "eth0", "root", "netem", "delay", "250ms"]]
+    );
+    assert_eq!(
+        Fault::PacketLoss(0.25).commands(),
+        vec![vec!["tc", "qdisc", "replace", "dev", "eth0", "root", "netem", "loss", "25%"]]
+    );
+    assert_eq!(
+        Fault::Partition.commands(),
+        vec![
+            vec!["iptables", "-A", "INPUT", "!", "-i", "lo", "-j", "DROP"],
+            vec!["iptables", "-A", "OUTPUT", "!", "-o", "lo", "-j", "DROP"],
+        ]
+    );
+    
+    println!("✅ Network fault commands test passed");
+}
+
+#[test]
+fn test_remove_volumes_setting() {
+    println!("🧪 Testing volume removal setting...");
+    
+    // Anonymous volumes go with the container unless asked to keep them
+    assert!(ContainerConfig::new("postgres:13-alpine").remove_volumes);
+    assert!(!ContainerConfig::new("postgres:13-alpine").remove_volumes(false).remove_volumes);
+    
+    println!("✅ Volume removal setting test passed");
+}
+
+#[test]
+fn test_stop_timeout_setting() {
+    println!("🧪 Testing stop grace period setting...");
+    
+    // Docker's own default applies unless a grace period is configured
+    assert_eq!(ContainerConfig::new("postgres:13-alpine").stop_timeout, None);
+    let container = ContainerConfig::new("postgres:13-alpine").stop_timeout(Duration::from_secs(30));
+    assert_eq!(container.stop_timeout, Some(Duration::from_secs(30)));
+    
+    println!("✅ Stop grace period setting test passed");
+}
+
+#[test]
+fn test_stop_with_huge_grace_period() {
+    println!("🧪 Testing stop with a huge grace period...");
+    
+    // The request's grace and the wait around it clamp instead of overflowing; stopping a
+    // container that doesn't exist is fine with or without Docker
+    let container = ContainerConfig::new("postgres:13-alpine").stop_timeout(Duration::MAX);
+    let _ = container.stop("no-such-container-for-huge-grace");
+    
+    println!("✅ Stop with a huge grace period test passed");
+}
+
+#[test]
+fn test_volume_bind_mounts() {
+    println!("🧪 Testing volume bind mounts...");
+    
+    let init_dir = std::env::temp_dir();
+    let container = ContainerConfig::new("postgres:13-alpine")
+        .volume(init_dir.to_str().unwrap(), "/docker-entrypoint-initdb.d");
+    assert_eq!(container.volumes, vec![(init_dir.to_str().unwrap().to_string(), "/docker-entrypoint-initdb.d".to_string())]);
+    assert_eq!(container.validate(), Ok(()));
+    
+    // A missing host path is caught before Docker would silently create an empty directory
+    let missing = ContainerConfig::new("postgres:13-alpine").volume("/no/such/init-sql", "/docker-entrypoint-initdb.d");
+    assert_eq!(missing.validate().unwrap_err(), vec![
+        "volume host path \"/no/such/init-sql\" (for /docker-entrypoint-initdb.d) does not exist".to_string(),
+    ]);
+    let error = missing.start().unwrap_err().to_string();
+    assert!(error.contains("/no/such/init-sql"), "{}", error);
+    
+    println!("✅ Volume bind mounts test passed");
+}
+
+#[test]
+fn test_create_and_remove_network() {
+    println!("🧪 Testing network creation...");
+    
+    let container = ContainerConfig::new("postgres:13-alpine").network("orders-net").network_alias("db");
+    assert_eq!(container.network.as_deref(), Some("orders-net"));
+    assert_eq!(container.network_aliases, vec!["db".to_string()]);
+    
+    // Against a real daemon the network is created and removed; without one both fail cleanly
+    let name = format!("harness-network-test-{}", std::process::id());
+    match create_network(&name) {
+        Ok(network_id) => {
+            assert!(!network_id.is_empty());
+            assert_eq!(create_network(&name).unwrap(), network_id, "an existing network is reused");
+            remove_network(&name).unwrap();
+        }
+        Err(e) => {
+            println!("⚠️  Docker not available: {}", e);
+            assert!(remove_network(&name).is_err());
+        }
+    }
+    
+    println!("✅ Network creation test passed");
+}
+
+#[test]
+fn test_remove_volume_fails_for_unknown_volumes() {
+    // With a daemon the volume doesn't exist; without one Docker can't be reached
+    let name = format!("harness-volume-test-{}", std::process::id());
+    assert!(remove_volume(&name).is_err());
+}
+
+#[test]
+fn test_commit_rejects_invalid_image_tags() {
+    println!("🧪 Testing commit image tag validation...");
+    
+    // Malformed tags are rejected before Docker is contacted
+    let container = ContainerConfig::new("alpine:latest");
+    for bad_tag in ["", "seeded:", ":v1", "localhost:5000/", "seeded db:v1"] {
+        let err = container.commit("no-such-container", bad_tag).unwrap_err().to_string();
+        assert!(err.contains("Invalid image tag"), "{:?} gave: {}", bad_tag, err);
+    }
+    
+    println!("✅ Commit image tag validation test passed");
+}
+
+#[test]
+fn test_compose_stack_start_order() {
+    println!("🧪 Testing compose stack start order...");
+    
+    // Dependencies start first; otherwise services keep the order they were added in
+    let stack = ComposeStack::new("shop")
+        .service("app", ContainerConfig::new("shop:test"), &["db", "cache"])
+        .service("cache", ContainerConfig::new("redis:alpine"), &[])
+        .service("db", ContainerConfig::new("postgres:13-alpine"), &["cache"])
+        .service("metrics", ContainerConfig::new("prom/prometheus"), &[]);
+    assert_eq!(stack.start_order().unwrap(), vec!["cache", "db", "app", "metrics"]);
+    
+    let unknown = ComposeStack::new("shop")
+        .service("app", ContainerConfig::new("shop:test"), &["dbb"]);
+    assert_eq!(unknown.start_order().unwrap_err(), "service 'app' depends on unknown service 'dbb'");
+    
+    let cycle = ComposeStack::new("shop")
+        .service("metrics", ContainerConfig::new("prom/prometheus"), &[])
+        .service("a", ContainerConfig::new("alpine:latest"), &["b"])
+        .service("b", ContainerConfig::new("alpine:latest"), &["a"]);
+    assert_eq!(cycle.start_order().unwrap_err(), "dependency cycle between services: a, b");
+    
+    let duplicate = ComposeStack::new("shop")
+        .service("db", ContainerConfig::new("postgres:13-alpine"), &[])
+        .service("db", ContainerConfig::new("mysql:8"), &[]);
+    assert!(duplicate.start_order().is_err());
+    
+    // Aliases only make sense on a network
+    let problems = ContainerConfig::new("redis:alpine").network_alias("cache").validate().unwrap_err();
+    assert_eq!(problems, vec!["network aliases need a network".to_string()]);
+    assert!(ContainerConfig::new("redis:alpine").network("shop-net").network_alias("cache").validate().is_ok());
+    
+    println!("✅ Compose stack start order test passed");
+}
+
+#[test]
+fn test_ready_poll_backoff() {
+    println!("🧪 Testing readiness poll strategies...");
+    
+    // The default keeps the fixed half-second cadence
+    let container = ContainerConfig::new("postgres:13-alpine");
+    assert_eq!(container.ready_poll, PollStrategy::Fixed(Duration::from_millis(500)));
+    assert_eq!(container.ready_poll.delay(7), Duration::from_millis(500));
+    
+    let backoff = PollStrategy::ExponentialBackoff {
+        initial: Duration::from_millis(50),
+        max: Duration::from_millis(1000),
+        jitter: 0.25,
+    };
+    let delays: Vec<u128> = (0..7).map(|attempt| backoff.base_delay(attempt).as_millis()).collect();
+    assert_eq!(delays, vec![50, 100, 200, 400, 800, 1000, 1000]);
+    assert_eq!(backoff.base_delay(u32::MAX), Duration::from_millis(1000));
+    
+    // Jitter stays within the configured fraction
+    for _ in 0..100 {
+        let delay = backoff.delay(3);
+        assert!(delay >= Duration::from_millis(300) && delay <= Duration::from_millis(500), "{:?}", delay);
+    }
+    
+    let container = ContainerConfig::new("postgres:13-alpine").ready_poll(PollStrategy::exponential());
+    assert_eq!(container.ready_poll, PollStrategy::exponential());
+    
+    println!("✅ Readiness poll strategies test passed");
+}
+
+#[test]
+fn test_container_config_validate_lists_problems() {
+    println!("🧪 Testing container config validation...");
+    
+    let valid = ContainerConfig::new("nginx:alpine")
+        .port(8080, 80)
+        .auto_port(443)
+        .name("web_server-1.test");
+    assert_eq!(valid.validate(), Ok(()));
+    
+    // Every problem is reported, not just the first
+    let invalid = ContainerConfig::new(" ")
+        .port(8080, 80)
+        .port(8080, 8000)
+        .port(8080, 9000)
+        .auto_port(80)
+        .name("bad name!");
+    let problems = invalid.validate().unwrap_err();
+    assert_eq!(problems, vec![
+        "image is empty".to_string(),
+        "host port 8080 is mapped more than once".to_string(),
+        "container port 80 is both mapped manually and auto-assigned".to_string(),
+        "container name \"bad name!\" must match [a-zA-Z0-9][a-zA-Z0-9_.-]*".to_string(),
+    ]);
+    
+    // start() fails fast before talking to Docker
+    let error = invalid.start().unwrap_err().to_string();
+    assert!(error.contains("Invalid container config") && error.contains("host port 8080"), "{}", error);
+    
+    let leading_dash = ContainerConfig::new("nginx:alpine").name("-web");
+    assert!(leading_dash.validate().is_err());
+    
+    println!("✅ Container config validation test passed");
+}
+
+#[test]
+fn test_daemon_info_report_metadata() {
+    println!("🧪 Testing Docker daemon info...");
+    
+    let daemon = DaemonInfo {
+        version: Some("24.0.7".to_string()),
+        api_version: Some("1.43".to_string()),
+        os: Some("linux".to_string()),
+        arch: Some("amd64".to_string()),
+        operating_system: None,
+        kernel_version: None,
+    };
+    assert_eq!(daemon.report_metadata(), vec![
+        ("docker.version".to_string(), "24.0.7".to_string()),
+        ("docker.api_version".to_string(), "1.43".to_string()),
+        ("docker.os".to_string(), "linux".to_string()),
+        ("docker.arch".to_string(), "amd64".to_string()),
+    ]);
+    
+    // Querying a real daemon either describes it or fails cleanly when Docker isn't available
+    match ContainerConfig::daemon_info() {
+        Ok(daemon) => assert!(daemon.version.is_some(), "A reachable daemon reports its version"),
+        Err(e) => println!("⚠️  Docker not available: {}", e),
+    }
+    
+    println!("✅ Docker daemon info test passed");
+}
+
+/// Answer each connection on a local listener with the next status in `statuses`, repeating the last one
+fn serve_statuses(statuses: &'static [u16]) -> u16 {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let host_port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for (i, stream) in listener.incoming().enumerate() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let status = statuses[i.min(statuses.len() - 1)];
+            let _ = write!(stream, "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+        }
+    });
+    host_port
+}
+
+fn info_for_port(host_port: u16) -> ContainerInfo {
+    ContainerInfo {
+        container_id: "http_probe_test".to_string(),
+        image: "test".to_string(),
+        name: None,
+        urls: vec![format!("http://localhost:{}", host_port)],
+        port_mappings: vec![(host_port, 80)],
+        auto_cleanup: false,
+        labels: Default::default(),
+    }
+}
+
+#[test]
+fn test_wait_http_ok_retries_until_healthy() {
+    // The service reports 503 twice while "starting", then 200
+    let container_info = info_for_port(serve_statuses(&[503, 503, 200]));
+    assert!(container_info.wait_http_ok(80, "/health", Duration::from_secs(5)).is_ok());
+    
+    let err = container_info.wait_http_ok(443, "/health", Duration::from_secs(1)).unwrap_err();
+    assert!(err.to_string().contains("no host port mapped for container port 443"));
+}
+
+#[test]
+fn test_wait_http_ok_times_out_when_never_healthy() {
+    let container_info = info_for_port(serve_statuses(&[500]));
+    let err = container_info.wait_http_ok(80, "/health", Duration::from_millis(300)).unwrap_err();
+    assert!(matches!(err, TestError::Timeout(timeout) if timeout == Duration::from_millis(300)), "unexpected error: {:?}", err);
+}
+
+#[test]
+fn test_container_logs_without_a_container() {
+    let container_info = ContainerInfo {
+        container_id: "no_such_container_for_logs".to_string(),
+        image: "test".to_string(),
+        name: None,
+        urls: Vec::new(),
+        port_mappings: Vec::new(),
+        auto_cleanup: false,
+        labels: Default::default(),
+    };
+    
+    // Without Docker the logs are a placeholder; with it, the daemon reports the missing container
+    match container_info.logs() {
+        Ok(placeholder) => assert!(placeholder.contains("logs unavailable"), "unexpected logs: {}", placeholder),
+        Err(e) => assert!(e.to_string().contains("no_such_container_for_logs"), "unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn test_failed_test_report_includes_container_logs() {
+    let started = std::sync::Arc::new(std::sync::Mutex::new(None));
+    let started_in_test = started.clone();
+    test("fails_after_starting_container", move |ctx| {
+        let info = match ContainerConfig::new("alpine:latest").start() {
+            Ok(info) => info,
+            Err(e) => return ctx.skip(&format!("Docker not available: {}", e)),
+        };
+        *started_in_test.lock().unwrap() = Some(info.container_id.clone());
+        Err("service misbehaved".into())
+    });
+    
+    let config = TestConfig {
+        html_report: Some("test_container_logs_report.html".to_string()),
+        ..Default::default()
+    };
+    let result = run_tests_with_config(config);
+    
+    let Some(container_id) = started.lock().unwrap().clone() else {
+        println!("⚠️  Docker not available, container log capture not exercised");
+        return;
+    };
+    assert_eq!(result, 1);
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html = std::fs::read_to_string(format!("{}/test-reports/test_container_logs_report.html", target_dir)).unwrap();
+    assert!(html.contains(&format!("Container {} logs", container_id)), "report should show the container's logs");
+}
+
+#[test]
+fn test_container_logs_captured_before_after_each_stops_it() {
+    // The logs must be fetched when the test fails, not after after_each has removed the container
+    let started = std::sync::Arc::new(std::sync::Mutex::new(None::<String>));
+    let (started_in_hook, started_in_teardown) = (started.clone(), started.clone());
+    before_each(move |ctx| {
+        let info = match ContainerConfig::new("alpine:latest").start() {
+            Ok(info) => info,
+            Err(e) => return ctx.skip(&format!("Docker not available: {}", e)),
+        };
+        *started_in_hook.lock().unwrap() = Some(info.container_id.clone());
+        Ok(())
+    });
+    after_each(move |_| {
+        if let Some(container_id) = started_in_teardown.lock().unwrap().as_ref() {
+            ContainerConfig::new("alpine:latest").stop(container_id)?;
+        }
+        Ok(())
+    });
+    test("fails_with_hook_container", |_| Err("service misbehaved".into()));
+    
+    let config = TestConfig {
+        html_report: Some("test_hook_container_logs_report.html".to_string()),
+        ..Default::default()
+    };
+    let result = run_tests_with_config(config);
+    
+    let Some(container_id) = started.lock().unwrap().clone() else {
+        println!("⚠️  Docker not available, container log capture not exercised");
+        return;
+    };
+    assert_eq!(result, 1);
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html = std::fs::read_to_string(format!("{}/test-reports/test_hook_container_logs_report.html", target_dir)).unwrap();
+    assert!(html.contains(&format!("Container {} logs", container_id)), "report should show the logs of the container after_each stopped");
+}
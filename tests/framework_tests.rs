@@ -96,6 +96,7 @@ fn test_test_filtering() {
         html_report: None,
         skip_hooks: None,
         timeout_config: TimeoutConfig::default(),
+        ..Default::default()
     };
     
     test("filtering_first_test_unique", |_| Ok(()));
@@ -121,6 +122,7 @@ fn test_tag_filtering() {
         html_report: None,
         skip_hooks: None,
         timeout_config: TimeoutConfig::default(),
+        ..Default::default()
     };
     
     test("tag_filtering_untagged_test_unique", |_| Ok(()));
@@ -266,6 +268,54 @@ fn test_shuffle_config() {
     assert_eq!(config.shuffle_seed, Some(12345));
 }
 
+#[test]
+fn test_string_shuffle_seed_is_deterministic() {
+    use rust_test_harness::{seed_from_str, shuffle_with_seed};
+    use std::sync::{Arc, Mutex};
+    
+    assert_eq!(seed_from_str("main"), seed_from_str("main"));
+    assert_ne!(seed_from_str("main"), seed_from_str("develop"));
+    
+    // The reusable shuffle is a permutation and depends only on the seed
+    let mut first: Vec<u32> = (0..20).collect();
+    let mut second = first.clone();
+    shuffle_with_seed(&mut first, 7);
+    shuffle_with_seed(&mut second, 7);
+    assert_eq!(first, second);
+    let mut sorted = first.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    
+    // A string seed runs tests in the same order as its hashed numeric seed
+    let run_order = |config: TestConfig| {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for i in 0..8 {
+            let order = Arc::clone(&order);
+            test(&format!("seeded_order_{}", i), move |_| {
+                order.lock().unwrap().push(i);
+                Ok(())
+            });
+        }
+        assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+        let order = order.lock().unwrap().clone();
+        order
+    };
+    let from_str = run_order(TestConfig {
+        shuffle_seed_str: Some("feature/login".to_string()),
+        max_concurrency: Some(1),
+        skip_hooks: None,
+        ..Default::default()
+    });
+    let from_num = run_order(TestConfig {
+        shuffle_seed: Some(seed_from_str("feature/login")),
+        max_concurrency: Some(1),
+        skip_hooks: None,
+        ..Default::default()
+    });
+    assert_eq!(from_str.len(), 8);
+    assert_eq!(from_str, from_num);
+}
+
 #[test]
 fn test_color_config() {
     let config = TestConfig {
@@ -374,22 +424,53 @@ fn test_config_environment_override() {
 
 #[test]
 fn test_concurrent_test_registration() {
-    // Test that tests can be registered from multiple threads safely
+    // Test that tests registered on other threads run once their registries are merged
+    use rust_test_harness::{merge_thread_registry, take_thread_registry, SharedLog};
     
-    let handle1 = std::thread::spawn(|| {
-        test("concurrent_test_1", |_| Ok(()));
+    let ran = SharedLog::new();
+    
+    let ran1 = ran.clone();
+    let handle1 = std::thread::spawn(move || {
+        test("concurrent_test_1", move |_| {
+            ran1.push("concurrent_test_1");
+            Ok(())
+        });
+        take_thread_registry()
     });
     
-    let handle2 = std::thread::spawn(|| {
-        test("concurrent_test_2", |_| Ok(()));
+    let ran2 = ran.clone();
+    let handle2 = std::thread::spawn(move || {
+        test("concurrent_test_2", move |_| {
+            ran2.push("concurrent_test_2");
+            Ok(())
+        });
+        before_each(|ctx| {
+            ctx.set_data("merged_hook_ran", true);
+            Ok(())
+        });
+        take_thread_registry()
     });
     
-    handle1.join().unwrap();
-    handle2.join().unwrap();
+    let registry1 = handle1.join().unwrap();
+    let registry2 = handle2.join().unwrap();
+    assert_eq!(registry1.test_count(), 1);
+    merge_thread_registry(registry1);
+    merge_thread_registry(registry2);
+    
+    // A test registered on this thread runs alongside them, and sees the merged hook
+    let ran3 = ran.clone();
+    test("concurrent_test_main", move |ctx| {
+        assert_eq!(ctx.get_data::<bool>("merged_hook_ran"), Some(&true));
+        ran3.push("concurrent_test_main");
+        Ok(())
+    });
     
-    // Run the tests to ensure they were registered correctly
     let result = rust_test_harness::run_tests();
     assert_eq!(result, 0);
+    
+    let mut ran = ran.entries();
+    ran.sort();
+    assert_eq!(ran, vec!["concurrent_test_1", "concurrent_test_2", "concurrent_test_main"]);
 }
 
 #[test]
@@ -445,4 +526,1510 @@ fn test_framework_recovery() {
     assert_eq!(result2, 0); // Should pass
     
     info!("✅ Framework successfully recovered from failures!");
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_test_with_ctx_clones_shared_value() {
+    // Test that test_with_ctx hands every registered test its own clone of the shared value
+    use rust_test_harness::test_with_ctx;
+    use std::sync::{Arc, Mutex};
+    
+    let visits = Arc::new(Mutex::new(Vec::new()));
+    
+    for i in 0..3 {
+        test_with_ctx(&format!("shared_ctx_test_{}", i), Arc::clone(&visits), move |_ctx, visits| {
+            visits.lock().unwrap().push(i);
+            Ok(())
+        });
+    }
+    
+    let result = rust_test_harness::run_tests();
+    assert_eq!(result, 0);
+    
+    let mut seen = visits.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(seen, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_test_matrix_expands_every_combination() {
+    // Test that test_matrix registers one filterable, tagged test per combination
+    use rust_test_harness::test_matrix;
+    use std::sync::{Arc, Mutex};
+    
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&seen);
+    
+    test_matrix("matrix_db", &[&["sqlite", "postgres"], &["v1", "v2"]], move |_ctx, values| {
+        recorder.lock().unwrap().push(values.join("+"));
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        filter: Some("matrix_db[".to_string()),
+        skip_tags: vec!["matrix:postgres,v2".to_string()],
+        ..Default::default()
+    };
+    
+    let result = rust_test_harness::run_tests_with_config(config);
+    assert_eq!(result, 0);
+    
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(seen, vec!["postgres+v1", "sqlite+v1", "sqlite+v2"]);
+}
+
+#[test]
+fn test_test_matrix_cases_keep_declared_order() {
+    // Test that matrix cases are listed in declared order and selected together by base name
+    use rust_test_harness::test_matrix;
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let report_path = format!("{}/test-reports/test_matrix_order.txt", target_dir);
+    
+    test_matrix("matrix_order", &[&["sqlite", "postgres", "mysql"], &["v2", "v1"]], |_ctx, values| {
+        // Later cases finish first, so completion order differs from declared order
+        if values[0] == "sqlite" {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        Ok(())
+    });
+    test("matrix_order_unrelated", |_| Ok(()));
+    
+    let config = TestConfig {
+        only: vec!["matrix_order".to_string()],
+        max_concurrency: Some(4),
+        text_report: Some(report_path.clone().into()),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    let listed: Vec<&str> = report.lines().take_while(|line| !line.is_empty()).collect();
+    assert_eq!(listed, vec![
+        "PASS matrix_order[sqlite,v2] <100ms",
+        "PASS matrix_order[sqlite,v1] <100ms",
+        "PASS matrix_order[postgres,v2] <100ms",
+        "PASS matrix_order[postgres,v1] <100ms",
+        "PASS matrix_order[mysql,v2] <100ms",
+        "PASS matrix_order[mysql,v1] <100ms",
+        "NOT RUN matrix_order_unrelated -",
+    ]);
+    
+    let _ = std::fs::remove_file(&report_path);
+}
+
+#[test]
+fn test_until_failure_stops_at_first_failing_iteration() {
+    // Test that until_failure reruns the suite and stops as soon as an iteration fails
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    let runs = Arc::new(AtomicUsize::new(0));
+    let before_all_runs = Arc::new(AtomicUsize::new(0));
+    
+    let counter = Arc::clone(&before_all_runs);
+    before_all(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+    let counter = Arc::clone(&runs);
+    test("flaky_on_third_run", move |_| {
+        if counter.fetch_add(1, Ordering::SeqCst) == 2 {
+            return Err("flaked".into());
+        }
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        until_failure: Some(10),
+        ..Default::default()
+    };
+    
+    let result = rust_test_harness::run_tests_with_config(config);
+    assert_eq!(result, 1);
+    assert_eq!(runs.load(Ordering::SeqCst), 3, "Should stop after the failing iteration");
+    assert_eq!(before_all_runs.load(Ordering::SeqCst), 1, "before_all runs once for the whole hunt");
+}
+
+#[test]
+fn test_until_failure_passes_when_cap_is_reached() {
+    // Test that a stable suite runs the full number of iterations and passes
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&runs);
+    test("stable_test", move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        until_failure: Some(4),
+        ..Default::default()
+    };
+    
+    let result = rust_test_harness::run_tests_with_config(config);
+    assert_eq!(result, 0);
+    assert_eq!(runs.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn test_context_reports_effective_concurrency() {
+    // Test that tests can see whether they run in parallel and with how many workers
+    use std::sync::{Arc, Mutex};
+    
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    for i in 0..3 {
+        let seen = Arc::clone(&seen);
+        test(&format!("concurrency_probe_{}", i), move |ctx| {
+            seen.lock().unwrap().push((ctx.is_parallel(), ctx.worker_count()));
+            Ok(())
+        });
+    }
+    let config = TestConfig {
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(*seen.lock().unwrap(), vec![(true, 2); 3]);
+    
+    seen.lock().unwrap().clear();
+    let probe = Arc::clone(&seen);
+    rust_test_harness::test_with_timeout("sequential_probe", Duration::from_secs(5), move |ctx| {
+        probe.lock().unwrap().push((ctx.is_parallel(), ctx.worker_count()));
+        Ok(())
+    });
+    let config = TestConfig {
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(*seen.lock().unwrap(), vec![(false, 1)]);
+}
+
+#[test]
+fn test_test_group_shares_setup_and_teardown() {
+    // Test that a group's setup runs once before its tests and teardown once after them
+    use rust_test_harness::test_group;
+    use std::sync::{Arc, Mutex};
+    
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let log = |events: &Arc<Mutex<Vec<String>>>, event: &str| events.lock().unwrap().push(event.to_string());
+    
+    let e = Arc::clone(&events);
+    test_group("accounts", move |group| {
+        let (e1, e2, e3, e4) = (Arc::clone(&e), Arc::clone(&e), Arc::clone(&e), Arc::clone(&e));
+        group.setup(move |_| { log(&e1, "setup"); Ok(()) });
+        group.test("create", move |_| { log(&e2, "test"); Ok(()) });
+        group.test("delete", move |_| { log(&e3, "test"); Ok(()) });
+        group.teardown(move |_| { log(&e4, "teardown"); Ok(()) });
+    });
+    let e = Arc::clone(&events);
+    after_all(move |_| { log(&e, "after_all"); Ok(()) });
+    
+    let config = TestConfig {
+        filter: Some("accounts::".to_string()),
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(*events.lock().unwrap(), vec!["setup", "test", "test", "teardown", "after_all"]);
+}
+
+#[test]
+fn test_test_group_teardown_runs_when_tests_are_filtered_out() {
+    // Test that teardown still runs at the end when some group tests never ran
+    use rust_test_harness::test_group;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    let teardowns = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&teardowns);
+    test_group("partial", move |group| {
+        group.test("selected", |_| Ok(()));
+        group.test("filtered_out", |_| Ok(()));
+        group.teardown(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+    });
+    
+    let config = TestConfig {
+        filter: Some("partial::selected".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(teardowns.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_test_group_setup_failure_fails_every_test() {
+    // Test that a failing group setup fails the group's tests without running them
+    use rust_test_harness::test_group;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    let runs = Arc::new(AtomicUsize::new(0));
+    let (r1, r2) = (Arc::clone(&runs), Arc::clone(&runs));
+    test_group("broken", move |group| {
+        group.setup(|_| Err("database unreachable".into()));
+        group.test("first", move |_| { r1.fetch_add(1, Ordering::SeqCst); Ok(()) });
+        group.test("second", move |_| { r2.fetch_add(1, Ordering::SeqCst); Ok(()) });
+    });
+    
+    assert_eq!(rust_test_harness::run_tests(), 1);
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_runtime_skip_does_not_fail_by_default() {
+    // A test that skips itself is reported as skipped, not failed
+    
+    test("runtime_skip_via_ctx", |ctx| ctx.skip("docker unavailable"));
+    test("runtime_skip_via_macro", |_| {
+        rust_test_harness::skip!("missing {}", "API_KEY");
+    });
+    test("runtime_skip_neighbour_passes", |_| Ok(()));
+    
+    let result = rust_test_harness::run_tests();
+    assert_eq!(result, 0);
+}
+
+#[test]
+fn test_require_env_helpers() {
+    // Missing env vars fail or skip with a message naming the variable
+    use rust_test_harness::TestContext;
+    
+    let ctx = TestContext::new();
+    std::env::set_var("RTH_REQUIRE_ENV_PRESENT", "postgres://localhost");
+    assert_eq!(ctx.require_env("RTH_REQUIRE_ENV_PRESENT"), Ok("postgres://localhost".to_string()));
+    assert_eq!(ctx.require_env_or_skip("RTH_REQUIRE_ENV_PRESENT"), Ok("postgres://localhost".to_string()));
+    
+    assert_eq!(
+        ctx.require_env("RTH_REQUIRE_ENV_MISSING"),
+        Err(TestError::Message("missing env var RTH_REQUIRE_ENV_MISSING".to_string()))
+    );
+    assert_eq!(
+        ctx.require_env_or_skip("RTH_REQUIRE_ENV_MISSING"),
+        Err(TestError::Skipped("missing env var RTH_REQUIRE_ENV_MISSING".to_string()))
+    );
+    
+    // A skipped requirement makes the test skipped, not failed
+    test("require_env_or_skip_test", |ctx| {
+        let _api_key = ctx.require_env_or_skip("RTH_REQUIRE_ENV_MISSING")?;
+        Err("should have been skipped".into())
+    });
+    assert_eq!(rust_test_harness::run_tests(), 0);
+}
+
+#[test]
+fn test_seeded_shuffle_is_a_fixed_permutation() {
+    // Orders are bisected by seed, so a seed must keep producing exactly this permutation
+    use rust_test_harness::{shuffle_with_seed, TestRng};
+    
+    let mut items: Vec<u32> = (0..10).collect();
+    shuffle_with_seed(&mut items, 42);
+    assert_eq!(items, vec![8, 3, 6, 5, 4, 0, 9, 2, 1, 7]);
+    let mut items: Vec<u32> = (0..10).collect();
+    shuffle_with_seed(&mut items, 0);
+    assert_eq!(items, vec![4, 9, 2, 5, 1, 7, 6, 0, 3, 8]);
+    
+    // SplitMix64's reference output for seed 0
+    assert_eq!(TestRng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+}
+
+#[test]
+fn test_context_rng_is_reproducible_per_test() {
+    // Each test gets its own stream, and the same suite seed replays it
+    use std::sync::{Arc, Mutex};
+    
+    let draws: Arc<Mutex<Vec<(String, u64)>>> = Arc::new(Mutex::new(Vec::new()));
+    for _ in 0..2 {
+        for name in ["rng_test_a", "rng_test_b"] {
+            let draws = Arc::clone(&draws);
+            test(name, move |ctx| {
+                let value = ctx.rng().next_u64();
+                draws.lock().unwrap().push((name.to_string(), value));
+                Ok(())
+            });
+        }
+        let config = TestConfig {
+            rng_seed: Some(42),
+            max_concurrency: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    }
+    
+    let draws = draws.lock().unwrap();
+    assert_eq!(draws.len(), 4);
+    assert_eq!(draws[0], draws[2], "Same seed and name replays the stream");
+    assert_eq!(draws[1], draws[3]);
+    assert_ne!(draws[0].1, draws[1].1, "Different tests get different streams");
+    
+    // The generator itself is deterministic and stays in range
+    let mut first = rust_test_harness::TestRng::new(7);
+    let mut second = rust_test_harness::TestRng::new(7);
+    for _ in 0..100 {
+        let value = first.gen_range(10..20);
+        assert_eq!(value, second.gen_range(10..20));
+        assert!((10..20).contains(&value));
+    }
+}
+
+#[test]
+fn test_only_runs_named_tests_in_order() {
+    // An explicit name list runs exactly those tests in the given order, and rejects unknown names
+    use rust_test_harness::SharedLog;
+    
+    let ran = SharedLog::new();
+    for name in ["only_a", "only_b", "only_c"] {
+        let ran = ran.clone();
+        test(name, move |_| {
+            ran.push(name);
+            Ok(())
+        });
+    }
+    let config = TestConfig {
+        only: vec!["only_c".to_string(), "only_a".to_string()],
+        filter: Some("only_b".to_string()),
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(ran.entries(), vec!["only_c", "only_a"]);
+    
+    ran.clear();
+    let ran_clone = ran.clone();
+    test("only_a", move |_| {
+        ran_clone.push("only_a");
+        Ok(())
+    });
+    let config = TestConfig {
+        only: vec!["only_a".to_string(), "only_typo".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 1);
+    assert!(ran.is_empty(), "Nothing runs when a named test doesn't exist");
+}
+
+#[test]
+fn test_requires_skips_tests_on_unfit_hosts() {
+    // Unmet requirements skip the test before its hooks run, naming every unmet one
+    use rust_test_harness::{test_requires, Requirement, Requirements, SharedLog};
+    
+    let busy = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+    let busy_port = busy.local_addr().unwrap().port();
+    
+    let unmet = Requirements::from([
+        Requirement::MinCores(1),
+        Requirement::MinCores(usize::MAX),
+        Requirement::Env("HARNESS_REQUIRES_NEVER_SET".to_string()),
+    ]);
+    let reason = unmet.check().unwrap_err();
+    assert!(reason.starts_with(&format!("unmet requirements: needs {} CPU cores, host has ", usize::MAX)), "{}", reason);
+    assert!(reason.ends_with("; env var HARNESS_REQUIRES_NEVER_SET is not set"), "{}", reason);
+    assert!(Requirement::PortFree(busy_port).check().unwrap_err().starts_with(&format!("port {} is not free", busy_port)));
+    
+    let ran = SharedLog::new();
+    let log = ran.clone();
+    before_each(move |_| {
+        log.push("before_each");
+        Ok(())
+    });
+    let log = ran.clone();
+    test_requires("requires_met", [Requirement::MinCores(1)], move |_| {
+        log.push("requires_met");
+        Ok(())
+    });
+    let log = ran.clone();
+    test_requires("requires_busy_port", vec![Requirement::PortFree(busy_port)], move |_| {
+        log.push("requires_busy_port");
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(ran.entries(), vec!["before_each", "requires_met"]);
+    drop(busy);
+}
+
+#[test]
+fn test_ignored_tests_are_reported_but_not_run() {
+    // A disabled test stays in the report as skipped, and run_ignored runs it anyway
+    use rust_test_harness::{test_ignored, SharedLog};
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_ignored_report.html", target_dir);
+    let ran = SharedLog::new();
+    
+    for run_ignored in [false, true] {
+        let log = ran.clone();
+        test_ignored("ignored_parked", "flaky on CI", move |_| {
+            log.push("ignored_parked");
+            Ok(())
+        });
+        let log = ran.clone();
+        test("ignored_neighbour", move |_| {
+            log.push("ignored_neighbour");
+            Ok(())
+        });
+        let config = TestConfig {
+            run_ignored,
+            // Disabling a test is deliberate, so it doesn't count as a runtime skip
+            fail_on_skip: true,
+            max_concurrency: Some(1),
+            html_report: Some("test_ignored_report.html".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+        
+        let html = std::fs::read_to_string(&html_path).unwrap();
+        assert!(html.contains("ignored_parked"));
+        assert_eq!(html.contains("disabled: flaky on CI"), !run_ignored);
+    }
+    assert_eq!(ran.entries(), vec!["ignored_neighbour", "ignored_parked", "ignored_neighbour"]);
+    let _ = std::fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_repeat_each_runs_tests_back_to_back() {
+    // Each selected test runs N times in a row, and every run is reported under its own name
+    use rust_test_harness::{SharedCounter, SharedLog};
+    
+    let ran = SharedLog::new();
+    let flaky_runs = SharedCounter::new();
+    let (log, runs) = (ran.clone(), flaky_runs.clone());
+    test("repeat_flaky", move |_| {
+        log.push("repeat_flaky");
+        // Every second run fails
+        if runs.increment() % 2 == 0 {
+            return Err("flaked".into());
+        }
+        Ok(())
+    });
+    let log = ran.clone();
+    test("repeat_stable", move |_| {
+        log.push("repeat_stable");
+        Ok(())
+    });
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let report_path = format!("{}/test-reports/test_repeat_each.txt", target_dir);
+    let config = TestConfig {
+        repeat_each: 3,
+        max_concurrency: Some(1),
+        text_report: Some(report_path.clone().into()),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 1);
+    assert_eq!(ran.entries(), vec![
+        "repeat_flaky", "repeat_flaky", "repeat_flaky",
+        "repeat_stable", "repeat_stable", "repeat_stable",
+    ]);
+    
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    assert_eq!(report, "PASS repeat_flaky#1 <100ms\nFAIL repeat_flaky#2 <100ms\nPASS repeat_flaky#3 <100ms\n\
+        PASS repeat_stable#1 <100ms\nPASS repeat_stable#2 <100ms\nPASS repeat_stable#3 <100ms\n\n\
+        6 tests: 5 passed, 1 failed, 0 skipped\n");
+    let _ = std::fs::remove_file(&report_path);
+}
+
+#[test]
+fn test_repeat_each_with_only_runs_every_repeat() {
+    // The repeats are renamed `name#N`, but `only` still selects them by the registered name
+    use rust_test_harness::SharedLog;
+    
+    let ran = SharedLog::new();
+    let log = ran.clone();
+    test("repeat_only_target", move |_| {
+        log.push("repeat_only_target");
+        Ok(())
+    });
+    test("repeat_only_other", |_| Ok(()));
+    
+    let report = rust_test_harness::run_tests_collect(TestConfig {
+        only: vec!["repeat_only_target".to_string()],
+        repeat_each: 2,
+        max_concurrency: Some(1),
+        ..Default::default()
+    });
+    assert_eq!(report.passed, 2);
+    assert_eq!(ran.entries(), vec!["repeat_only_target", "repeat_only_target"]);
+}
+
+#[test]
+fn test_selector_stages() {
+    // Each stage keeps or rejects a test with a reason; the first rejecting stage wins
+    use rust_test_harness::{SelectorStage, SkipReason, TestCase, TestSelector};
+    
+    let db_test = TestCase::named("db_migration", &["db", "slow"]);
+    let api_test = TestCase::named("api_login", &[]);
+    
+    assert_eq!(SelectorStage::NameContains("db".to_string()).check(&db_test), Ok(()));
+    assert_eq!(SelectorStage::NameContains("db".to_string()).check(&api_test), Err(SkipReason::Filtered));
+    assert_eq!(SelectorStage::ExactNames(vec!["api_login".to_string()]).check(&api_test), Ok(()));
+    assert_eq!(SelectorStage::ExactNames(vec!["api".to_string()]).check(&api_test), Err(SkipReason::Filtered));
+    assert_eq!(SelectorStage::ExcludeTags(vec!["slow".to_string()]).check(&db_test), Err(SkipReason::Tagged));
+    assert_eq!(SelectorStage::ExcludeTags(vec!["slow".to_string()]).check(&api_test), Ok(()));
+    
+    let selector = TestSelector::new()
+        .exclude_tags(&["slow"])
+        .custom(|test| test.name.starts_with("api_"));
+    assert_eq!(selector.select(&db_test), Err(SkipReason::Tagged));
+    assert_eq!(selector.select(&api_test), Ok(()));
+    assert_eq!(
+        selector.select(&TestCase::named("web_home", &[])),
+        Err(SkipReason::Deselected("custom selector".to_string()))
+    );
+    
+    // Config options become stages, followed by the configured selector
+    let config = TestConfig {
+        filter: Some("db".to_string()),
+        skip_tags: vec!["flaky".to_string()],
+        selector: TestSelector::new().name_contains("migration"),
+        ..Default::default()
+    };
+    assert_eq!(TestSelector::from_config(&config).stages.len(), 3);
+}
+
+#[test]
+fn test_custom_selector_limits_the_run() {
+    // A custom stage decides which registered tests run
+    use rust_test_harness::{SharedLog, TestSelector};
+    
+    let ran = SharedLog::new();
+    for name in ["custom_select_keep", "custom_select_drop"] {
+        let ran = ran.clone();
+        test(name, move |_| {
+            ran.push(name);
+            Ok(())
+        });
+    }
+    let config = TestConfig {
+        selector: TestSelector::new().custom(|test| test.name.ends_with("_keep")),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(ran.entries(), vec!["custom_select_keep"]);
+}
+
+#[test]
+fn test_fail_on_skip_fails_for_runtime_skips() {
+    // fail_on_skip turns an unexpected runtime skip into a non-zero exit
+    
+    test("fail_on_skip_runtime_skipped", |ctx| ctx.skip("docker unavailable"));
+    test("fail_on_skip_passing", |_| Ok(()));
+    
+    let config = TestConfig {
+        fail_on_skip: true,
+        ..Default::default()
+    };
+    
+    let result = rust_test_harness::run_tests_with_config(config);
+    assert_eq!(result, 1);
+}
+
+#[test]
+fn test_fail_on_skip_limited_to_reasons() {
+    // With fail_on_skip_reasons, only runtime skips whose reason matches one of them fail the run
+    use rust_test_harness::{run_tests_with_outcome, RunOutcome};
+    
+    let config = || TestConfig {
+        fail_on_skip: true,
+        fail_on_skip_reasons: vec!["Docker".to_string()],
+        ..Default::default()
+    };
+    
+    test("fail_on_skip_reasons_gpu", |ctx| ctx.skip("no GPU on this runner"));
+    test("fail_on_skip_reasons_passing", |_| Ok(()));
+    assert_eq!(run_tests_with_outcome(config()), RunOutcome::AllPassed);
+    
+    test("fail_on_skip_reasons_gpu", |ctx| ctx.skip("no GPU on this runner"));
+    test("fail_on_skip_reasons_docker", |ctx| ctx.skip("docker unavailable"));
+    assert_eq!(run_tests_with_outcome(config()), RunOutcome::RuntimeSkips(1));
+}
+
+#[test]
+fn test_fail_on_skip_ignores_filtered_tests() {
+    // Tests deselected by filter or tags are intentional and don't trip fail_on_skip
+    use rust_test_harness::test_with_tags;
+    
+    test("fail_on_skip_selected", |_| Ok(()));
+    test("fail_on_skip_other", |_| Ok(()));
+    test_with_tags("fail_on_skip_selected_slow", vec!["slow"], |_| Ok(()));
+    
+    let config = TestConfig {
+        filter: Some("selected".to_string()),
+        skip_tags: vec!["slow".to_string()],
+        fail_on_skip: true,
+        ..Default::default()
+    };
+    
+    let result = rust_test_harness::run_tests_with_config(config);
+    assert_eq!(result, 0);
+}
+
+#[test]
+fn test_clear_tests_only_keeps_hooks() {
+    // Clearing only tests leaves the registered hooks in place for the next batch
+    use rust_test_harness::clear_tests_only;
+    use std::sync::{Arc, Mutex};
+    
+    let hook_runs = Arc::new(Mutex::new(0));
+    let ran = Arc::new(Mutex::new(Vec::new()));
+    
+    let hook_runs_clone = Arc::clone(&hook_runs);
+    before_each(move |_| {
+        *hook_runs_clone.lock().unwrap() += 1;
+        Ok(())
+    });
+    
+    let ran_discarded = Arc::clone(&ran);
+    test("clear_only_discarded", move |_| {
+        ran_discarded.lock().unwrap().push("discarded");
+        Ok(())
+    });
+    
+    clear_tests_only();
+    
+    let ran_kept = Arc::clone(&ran);
+    test("clear_only_kept", move |_| {
+        ran_kept.lock().unwrap().push("kept");
+        Ok(())
+    });
+    
+    let result = rust_test_harness::run_tests();
+    assert_eq!(result, 0);
+    assert_eq!(*ran.lock().unwrap(), vec!["kept"]);
+    assert_eq!(*hook_runs.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_clear_tests_only_keeps_hooks_across_batches() {
+    // A run after clear_tests_only leaves the hooks registered, so every batch it comes before
+    // gets them; a run without it consumes them as usual
+    use rust_test_harness::{clear_tests_only, SharedLog};
+    
+    let log = SharedLog::new();
+    let (all_start, each_start, each_end, all_end) = (log.clone(), log.clone(), log.clone(), log.clone());
+    before_all(move |_| {
+        all_start.push("before_all");
+        Ok(())
+    });
+    before_each(move |_| {
+        each_start.push("before_each");
+        Ok(())
+    });
+    after_each(move |_| {
+        each_end.push("after_each");
+        Ok(())
+    });
+    after_all(move |_| {
+        all_end.push("after_all");
+        Ok(())
+    });
+    
+    for batch in ["first", "second", "third"] {
+        if batch != "third" {
+            clear_tests_only();
+        }
+        let ran = log.clone();
+        test(&format!("batch_{}", batch), move |_| {
+            ran.push(batch);
+            Ok(())
+        });
+        assert_eq!(rust_test_harness::run_tests(), 0);
+    }
+    assert_eq!(log.entries(), vec![
+        "before_all", "before_each", "first", "after_each", "after_all",
+        "before_all", "before_each", "second", "after_each", "after_all",
+        "before_all", "before_each", "third", "after_each", "after_all",
+    ]);
+    
+    // The third run consumed the hooks, so the next batch runs without them
+    let ran = log.clone();
+    test("batch_after_consuming_run", move |_| {
+        ran.push("fourth");
+        Ok(())
+    });
+    assert_eq!(rust_test_harness::run_tests(), 0);
+    assert_eq!(log.entries().last().map(String::as_str), Some("fourth"));
+    assert_eq!(log.entries().len(), 16);
+}
+
+#[test]
+fn test_clear_hooks_only_keeps_tests() {
+    // Clearing only hooks leaves the registered tests in place
+    use rust_test_harness::clear_hooks_only;
+    
+    before_each(|_| Err("this hook should have been cleared".into()));
+    test("clear_hooks_only_test", |_| Ok(()));
+    
+    clear_hooks_only();
+    
+    let result = rust_test_harness::run_tests();
+    assert_eq!(result, 0);
+}
+
+#[test]
+fn test_once_setup_runs_once_per_process() {
+    // once_setup survives repeated run_tests calls, unlike before_all
+    use rust_test_harness::{once_setup, run_tests_with_outcome, RunOutcome};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    
+    static SETUP_RUNS: AtomicUsize = AtomicUsize::new(0);
+    static EARLY_SETUP_RAN: AtomicBool = AtomicBool::new(false);
+    let before_all_runs = std::sync::Arc::new(AtomicUsize::new(0));
+    
+    // Runs that stop before starting any test keep the setup for the next run
+    once_setup(|| EARLY_SETUP_RAN.store(true, Ordering::SeqCst));
+    assert_eq!(run_tests_with_outcome(TestConfig::default()), RunOutcome::NoTestsMatched);
+    test("once_setup_test", |_| Ok(()));
+    let config = TestConfig { only: vec!["once_setup_typo".to_string()], ..Default::default() };
+    assert_eq!(run_tests_with_outcome(config), RunOutcome::UnknownTests(vec!["once_setup_typo".to_string()]));
+    assert!(!EARLY_SETUP_RAN.load(Ordering::SeqCst));
+    
+    for _ in 0..3 {
+        once_setup(|| {
+            SETUP_RUNS.fetch_add(1, Ordering::SeqCst);
+        });
+        let runs = std::sync::Arc::clone(&before_all_runs);
+        before_all(move |_| {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+        test("once_setup_test", |_| {
+            assert_eq!(SETUP_RUNS.load(Ordering::SeqCst), 1);
+            Ok(())
+        });
+        assert_eq!(rust_test_harness::run_tests(), 0);
+    }
+    
+    assert_eq!(SETUP_RUNS.load(Ordering::SeqCst), 1);
+    assert!(EARLY_SETUP_RAN.load(Ordering::SeqCst), "setup registered before an early return was dropped");
+    assert_eq!(before_all_runs.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_sample_keeps_a_seeded_fraction() {
+    // Sampling keeps the same subset for the same seed
+    use std::sync::{Arc, Mutex};
+    
+    let run_sampled = |seed: u64| {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        for i in 0..10 {
+            let ran = Arc::clone(&ran);
+            test(&format!("sampled_{}", i), move |_| {
+                ran.lock().unwrap().push(i);
+                Ok(())
+            });
+        }
+        let config = TestConfig {
+            sample: Some(0.3),
+            shuffle_seed: Some(seed),
+            skip_hooks: None,
+            ..Default::default()
+        };
+        assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+        let mut ran = ran.lock().unwrap().clone();
+        ran.sort();
+        ran
+    };
+    
+    let first = run_sampled(99);
+    assert_eq!(first.len(), 3);
+    assert_eq!(first, run_sampled(99));
+}
+
+#[test]
+fn test_order_policies() {
+    // Each ordering policy decides the sequence tests run in
+    use rust_test_harness::{test_with_tags, TestOrder};
+    use std::sync::{Arc, Mutex};
+    
+    let run_ordered = |order: TestOrder| {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        for (name, tags) in [("order_c", vec!["unit"]), ("order_a", vec![]), ("order_b", vec!["db"]), ("order_d", vec!["unit"])] {
+            let ran = Arc::clone(&ran);
+            test_with_tags(name, tags, move |_| {
+                ran.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+        let config = TestConfig {
+            order,
+            max_concurrency: Some(1),
+            skip_hooks: None,
+            ..Default::default()
+        };
+        assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+        let ran = ran.lock().unwrap().clone();
+        ran
+    };
+    
+    assert_eq!(run_ordered(TestOrder::Registration), vec!["order_c", "order_a", "order_b", "order_d"]);
+    assert_eq!(run_ordered(TestOrder::Alphabetical), vec!["order_a", "order_b", "order_c", "order_d"]);
+    assert_eq!(run_ordered(TestOrder::ByTag), vec!["order_b", "order_c", "order_d", "order_a"]);
+    assert_eq!(run_ordered(TestOrder::Shuffle(5)), run_ordered(TestOrder::Shuffle(5)));
+    
+    // Reverse alphabetical through a custom comparison
+    let reversed = run_ordered(TestOrder::Custom(Arc::new(|a, b| b.name.cmp(&a.name))));
+    assert_eq!(reversed, vec!["order_d", "order_c", "order_b", "order_a"]);
+}
+
+#[test]
+fn test_chunk_hooks_run_every_n_tests() {
+    // Chunk hooks fire around each run of N tests, and a short last chunk still gets after_chunk
+    use rust_test_harness::{after_chunk, before_chunk, SharedLog};
+    
+    let run_chunked = |max_concurrency: usize| {
+        let events = SharedLog::new();
+        let log = events.clone();
+        before_chunk(2, move |_| {
+            log.push("setup");
+            Ok(())
+        });
+        let log = events.clone();
+        after_chunk(2, move |_| {
+            log.push("reset");
+            Ok(())
+        });
+        for i in 1..=5 {
+            let log = events.clone();
+            test(&format!("chunked_{}", i), move |_| {
+                log.push("test");
+                Ok(())
+            });
+        }
+        let config = TestConfig {
+            max_concurrency: Some(max_concurrency),
+            skip_hooks: None,
+            ..Default::default()
+        };
+        assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+        events.entries().join(" ")
+    };
+    
+    // Parallel chunks are scheduled as units, so the order is the same either way
+    let expected = "setup test test reset setup test test reset setup test reset";
+    assert_eq!(run_chunked(1), expected);
+    assert_eq!(run_chunked(4), expected);
+}
+
+#[test]
+fn test_failing_before_chunk_fails_its_chunk() {
+    use rust_test_harness::before_chunk;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    
+    let chunks = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&chunks);
+    before_chunk(2, move |_| {
+        // Only the second chunk's setup fails
+        match seen.fetch_add(1, Ordering::SeqCst) {
+            1 => Err("tables still locked".into()),
+            _ => Ok(()),
+        }
+    });
+    let ran = Arc::new(AtomicUsize::new(0));
+    for i in 1..=4 {
+        let ran = Arc::clone(&ran);
+        test(&format!("chunk_failure_{}", i), move |_| {
+            ran.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+    }
+    let config = TestConfig {
+        max_concurrency: Some(1),
+        skip_hooks: None,
+        ..Default::default()
+    };
+    
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 1);
+    assert_eq!(ran.load(Ordering::SeqCst), 2, "The failed chunk's tests don't run");
+}
+
+#[test]
+fn test_run_outcome_explains_the_exit_code() {
+    use rust_test_harness::{run_tests_with_outcome, RunOutcome};
+    
+    let sequential = || TestConfig { max_concurrency: Some(1), skip_hooks: None, ..Default::default() };
+    
+    test("outcome_pass", |_| Ok(()));
+    assert_eq!(run_tests_with_outcome(sequential()), RunOutcome::AllPassed);
+    
+    test("outcome_fail_a", |_| Err("boom".into()));
+    test("outcome_fail_b", |_| Err("boom".into()));
+    test("outcome_pass_again", |_| Ok(()));
+    let outcome = run_tests_with_outcome(sequential());
+    assert_eq!(outcome, RunOutcome::TestsFailed(2));
+    assert_eq!(outcome.to_exit_code(), 1);
+    
+    // Setup failures are told apart from test failures
+    before_all(|_| Err("database unreachable".into()));
+    test("outcome_never_runs", |_| Ok(()));
+    let outcome = run_tests_with_outcome(sequential());
+    assert_eq!(outcome, RunOutcome::SetupFailed("before_all hook failed: database unreachable".to_string()));
+    assert_eq!(outcome.to_exit_code(), 1);
+    
+    test("outcome_filtered_out", |_| Ok(()));
+    let config = TestConfig { filter: Some("no_such_test".to_string()), ..sequential() };
+    let outcome = run_tests_with_outcome(config);
+    assert_eq!(outcome, RunOutcome::NoTestsMatched);
+    assert_eq!(outcome.to_exit_code(), 0);
+    
+    test("outcome_named", |_| Ok(()));
+    let config = TestConfig { only: vec!["outcome_nmaed".to_string()], ..sequential() };
+    assert_eq!(run_tests_with_outcome(config), RunOutcome::UnknownTests(vec!["outcome_nmaed".to_string()]));
+    
+    assert_eq!(RunOutcome::Aborted.to_exit_code(), 130);
+}
+
+#[test]
+fn test_exit_codes_per_outcome() {
+    use rust_test_harness::{run_tests_with_outcome, test_with_timeout, ExitCodeMap, RunOutcome};
+    
+    let codes = ExitCodeMap { setup_failed: 2, timed_out: 3, ..Default::default() };
+    let config = || TestConfig {
+        max_concurrency: Some(1),
+        skip_hooks: None,
+        exit_codes: codes.clone(),
+        ..Default::default()
+    };
+    
+    // Defaults keep the plain 0/1 codes
+    assert_eq!(TestConfig::default().exit_codes, ExitCodeMap::default());
+    assert_eq!(RunOutcome::TestsFailed(1).to_exit_code(), 1);
+    assert_eq!(RunOutcome::SetupFailed("x".to_string()).to_exit_code(), 1);
+    
+    before_all(|_| Err("registry down".into()));
+    test("exit_code_never_runs", |_| Ok(()));
+    assert_eq!(rust_test_harness::run_tests_with_config(config()), 2);
+    
+    test("exit_code_genuine_failure", |_| Err("wrong total".into()));
+    assert_eq!(rust_test_harness::run_tests_with_config(config()), 1);
+    
+    // A run whose only failures are timeouts is told apart from one with real failures
+    test_with_timeout("exit_code_slow", Duration::from_millis(20), |_| {
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    });
+    assert_eq!(run_tests_with_outcome(config()), RunOutcome::TimedOut(1));
+    test_with_timeout("exit_code_slow_again", Duration::from_millis(20), |_| {
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    });
+    assert_eq!(rust_test_harness::run_tests_with_config(config()), 3);
+    
+    test("exit_code_pass", |_| Ok(()));
+    assert_eq!(rust_test_harness::run_tests_with_config(config()), 0);
+}
+
+#[test]
+fn test_retries_rerun_failed_tests() {
+    // A failed test is re-run up to its retry count and only fails if every attempt does
+    use rust_test_harness::{test_with_retries, skip, SharedCounter};
+    
+    for max_concurrency in [Some(1), Some(2)] {
+        let (flaky, broken, skipping, unretried) = (SharedCounter::new(), SharedCounter::new(), SharedCounter::new(), SharedCounter::new());
+        let runs = flaky.clone();
+        test_with_retries("retry_flaky", 2, move |_| {
+            // Fails twice, then passes on the third and last attempt
+            if runs.increment() < 3 {
+                return Err("flaked".into());
+            }
+            Ok(())
+        });
+        let runs = broken.clone();
+        test("retry_broken", move |_| {
+            runs.increment();
+            Err("always broken".into())
+        });
+        let runs = skipping.clone();
+        test("retry_skipping", move |_| {
+            runs.increment();
+            skip!("not retried");
+        });
+        let runs = unretried.clone();
+        test_with_retries("retry_opted_out", 0, move |_| {
+            runs.increment();
+            Err("not worth retrying".into())
+        });
+        
+        let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+        let html_path = format!("{}/test-reports/test_retries.html", target_dir);
+        let config = TestConfig {
+            max_concurrency,
+            retries: Some(1),
+            html_report: Some("test_retries.html".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(rust_test_harness::run_tests_with_outcome(config), rust_test_harness::RunOutcome::TestsFailed(2));
+        assert_eq!(flaky.get(), 3, "the test's own retries win over the config's");
+        assert_eq!(unretried.get(), 1, "a test's own 0 retries win over the config's too");
+        assert_eq!(broken.get(), 2, "the config's retries apply to other tests");
+        assert_eq!(skipping.get(), 1, "skips aren't retried");
+        
+        let html = std::fs::read_to_string(&html_path).unwrap();
+        assert!(html.contains("passed on attempt 3 of 3"), "{}", html);
+        assert!(html.contains("failed all 2 attempts"), "{}", html);
+        let _ = std::fs::remove_file(&html_path);
+    }
+}
+
+#[test]
+fn test_retries_skip_timed_out_attempts() {
+    // A timed-out attempt keeps running in the background and holds the test, so it isn't retried
+    use rust_test_harness::{run_tests_collect, test_with_timeout, SharedCounter};
+    
+    for max_concurrency in [Some(1), Some(2)] {
+        let runs = SharedCounter::new();
+        let counter = runs.clone();
+        test_with_timeout("retry_times_out", Duration::from_millis(50), move |_| {
+            counter.increment();
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(())
+        });
+        
+        let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+        let html_path = format!("{}/test-reports/test_retried_timeout.html", target_dir);
+        let config = TestConfig {
+            max_concurrency,
+            retries: Some(2),
+            html_report: Some("test_retried_timeout.html".to_string()),
+            ..Default::default()
+        };
+        let report = run_tests_collect(config);
+        assert_eq!((report.failed, report.timeouts), (1, 1));
+        assert_eq!(runs.get(), 1);
+        
+        let html = std::fs::read_to_string(&html_path).unwrap();
+        assert!(!html.contains("failed all"), "the timeout was retried: {}", html);
+        let _ = std::fs::remove_file(&html_path);
+    }
+}
+
+#[test]
+fn test_flaky_tests_pass_the_run() {
+    // A test that passes only after a retry is reported as flaky, and doesn't fail the run
+    use rust_test_harness::{test_with_retries, SharedCounter};
+    
+    let runs = SharedCounter::new();
+    let counter = runs.clone();
+    test_with_retries("flaky_once", 1, move |_| {
+        if counter.increment() == 1 {
+            return Err("flaked".into());
+        }
+        Ok(())
+    });
+    test("flaky_neighbour", |_| Ok(()));
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let report_path = format!("{}/test-reports/test_flaky.txt", target_dir);
+    let html_path = format!("{}/test-reports/test_flaky.html", target_dir);
+    let config = TestConfig {
+        text_report: Some(report_path.clone().into()),
+        html_report: Some("test_flaky.html".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(rust_test_harness::run_tests_with_config(config), 0);
+    assert_eq!(runs.get(), 2);
+    
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    assert_eq!(report, "PASS flaky_neighbour <100ms\nFLAKY flaky_once <100ms\n\n2 tests: 1 passed, 0 failed, 0 skipped, 1 flaky\n");
+    let html = std::fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains(r#"<div class="test-item flaky""#), "{}", html);
+    assert!(html.contains(r#"<div class="label">Flaky</div>"#), "{}", html);
+    
+    let _ = std::fs::remove_file(&report_path);
+    let _ = std::fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_run_tests_collect_reports_results() {
+    // The collected report carries the counts and every test's result, not just an exit code
+    use rust_test_harness::{run_tests_collect, skip, ExitCodeMap, RunOutcome, TestStatus};
+    
+    test("collect_pass", |_| Ok(()));
+    test("collect_fail", |_| Err("off by one".into()));
+    test("collect_skip", |_| skip!("not today"));
+    let report = run_tests_collect(TestConfig { max_concurrency: Some(1), ..Default::default() });
+    
+    assert_eq!(report.outcome, RunOutcome::TestsFailed(1));
+    assert_eq!(report.exit_code(&ExitCodeMap::default()), 1);
+    assert_eq!((report.total, report.passed, report.flaky, report.failed, report.skipped), (3, 1, 0, 1, 1));
+    assert!(report.total_time > Duration::ZERO);
+    let results: Vec<(&str, &TestStatus)> = report.results.iter().map(|(name, status, _)| (name.as_str(), status)).collect();
+    assert_eq!(results, vec![
+        ("collect_pass", &TestStatus::Passed),
+        ("collect_fail", &TestStatus::Failed(TestError::Message("off by one".to_string()))),
+        ("collect_skip", &TestStatus::Skipped),
+    ]);
+    assert!(report.results.iter().all(|(_, _, duration)| duration.is_some()));
+    
+    // Runs that stop early still report every registered test
+    before_all(|_| Err("no database".into()));
+    test("collect_never_runs", |_| Ok(()));
+    let report = run_tests_collect(TestConfig::default());
+    assert!(matches!(report.outcome, RunOutcome::SetupFailed(_)));
+    assert_eq!(report.results, vec![("collect_never_runs".to_string(), TestStatus::Pending, None)]);
+}
+
+#[test]
+fn test_only_focuses_the_run() {
+    // While any test is registered with test_only, only those run, whatever the filter says
+    use rust_test_harness::{run_tests_collect, test_only, SharedLog, TestStatus};
+    
+    let ran = SharedLog::new();
+    for name in ["focus_regular", "focus_first", "focus_second"] {
+        let log = ran.clone();
+        let body = move |_: &mut rust_test_harness::TestContext| {
+            log.push(name);
+            Ok(())
+        };
+        if name == "focus_regular" {
+            test(name, body);
+        } else {
+            test_only(name, body);
+        }
+    }
+    let report = run_tests_collect(TestConfig {
+        filter: Some("focus_regular".to_string()),
+        max_concurrency: Some(1),
+        ..Default::default()
+    });
+    
+    assert_eq!(ran.entries(), vec!["focus_first", "focus_second"]);
+    assert_eq!((report.passed, report.skipped), (2, 1));
+    assert_eq!(report.results[0], ("focus_regular".to_string(), TestStatus::Skipped, None));
+    assert_eq!(report.exit_code(&Default::default()), 0);
+}
+
+#[test]
+fn test_only_tags_include_before_skip_tags() {
+    // only_tags keeps tests with a matching tag, then skip_tags drops from what's left
+    use rust_test_harness::{run_tests_collect, test_with_tags, SelectorStage, SharedLog, SkipReason, TestCase, TestStatus};
+    
+    let tagged = TestCase::named("orders_api", &["integration", "api"]);
+    assert_eq!(SelectorStage::IncludeTags(vec!["api".to_string()]).check(&tagged), Ok(()));
+    assert_eq!(SelectorStage::IncludeTags(vec!["unit".to_string()]).check(&tagged), Err(SkipReason::Tagged));
+    assert_eq!(SelectorStage::IncludeTags(vec!["unit".to_string()]).check(&TestCase::named("untagged", &[])), Err(SkipReason::Tagged));
+    
+    let ran = SharedLog::new();
+    for (name, tags) in [
+        ("tags_integration", vec!["integration"]),
+        ("tags_integration_slow", vec!["integration", "slow"]),
+        ("tags_unit", vec!["unit"]),
+        ("tags_none", vec![]),
+    ] {
+        let log = ran.clone();
+        test_with_tags(name, tags, move |_| {
+            log.push(name);
+            Ok(())
+        });
+    }
+    let report = run_tests_collect(TestConfig {
+        only_tags: vec!["integration".to_string()],
+        skip_tags: vec!["slow".to_string()],
+        max_concurrency: Some(1),
+        ..Default::default()
+    });
+    
+    // The test matching both lists is skipped
+    assert_eq!(ran.entries(), vec!["tags_integration"]);
+    let not_run: Vec<&str> = report.results.iter()
+        .filter(|(_, status, _)| *status != TestStatus::Passed)
+        .map(|(name, _, _)| name.as_str())
+        .collect();
+    assert_eq!(not_run, vec!["tags_integration_slow", "tags_unit", "tags_none"]);
+}
+
+#[test]
+fn test_fail_fast_stops_starting_tests() {
+    // After the first failure no further tests start; they're reported as skipped
+    use rust_test_harness::{run_tests_collect, SharedLog, TestStatus};
+    
+    let ran = SharedLog::new();
+    for name in ["ff_pass", "ff_fail", "ff_after_1", "ff_after_2"] {
+        let log = ran.clone();
+        test(name, move |_| {
+            log.push(name);
+            if name == "ff_fail" {
+                return Err("first failure".into());
+            }
+            Ok(())
+        });
+    }
+    let report = run_tests_collect(TestConfig { fail_fast: true, max_concurrency: Some(1), ..Default::default() });
+    assert_eq!(ran.entries(), vec!["ff_pass", "ff_fail"]);
+    assert_eq!((report.passed, report.failed, report.skipped), (1, 1, 2));
+    assert_eq!(report.results[2].1, TestStatus::Skipped);
+    
+    // In parallel, tests already running finish but queued ones never start
+    let ran = SharedLog::new();
+    test("ff_parallel_fail", |_| Err("first failure".into()));
+    for i in 0..20 {
+        let log = ran.clone();
+        test(&format!("ff_parallel_{}", i), move |_| {
+            std::thread::sleep(Duration::from_millis(20));
+            log.push(i.to_string());
+            Ok(())
+        });
+    }
+    let report = run_tests_collect(TestConfig { fail_fast: true, max_concurrency: Some(2), ..Default::default() });
+    let started = ran.entries().len();
+    assert!(started < 20, "all {} queued tests still started", started);
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.skipped, 20 - started);
+}
+
+#[test]
+fn test_report_counts_timeouts_separately() {
+    // Timeouts stay failures, but are also counted on their own so slow and broken can be told apart
+    use rust_test_harness::{run_tests_collect, test_with_timeout, RunOutcome};
+    
+    assert!(TestError::Timeout(Duration::from_secs(1)).is_timeout());
+    assert!(TestError::TimeoutAtCheckpoint { timeout: Duration::from_secs(1), checkpoint: "seeded".into() }.is_timeout());
+    assert!(!TestError::Message("broken".into()).is_timeout());
+    
+    test_with_timeout("timeouts_slow", Duration::from_millis(10), |_| {
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    });
+    test("timeouts_broken", |_| Err("broken".into()));
+    test("timeouts_ok", |_| Ok(()));
+    let report = run_tests_collect(TestConfig { max_concurrency: Some(1), ..Default::default() });
+    assert_eq!((report.passed, report.failed, report.timeouts), (1, 2, 1));
+    assert_eq!(report.outcome, RunOutcome::TestsFailed(2));
+}
+
+#[test]
+fn test_tagged_hooks_only_run_for_matching_tests() {
+    // A db-tagged hook fires for db tests only; untagged hooks still run for every test
+    use rust_test_harness::{after_each_tagged, before_each_tagged, run_tests_collect, test_with_tags, SharedLog};
+    
+    for max_concurrency in [Some(1), Some(2)] {
+        let calls = SharedLog::new();
+        let log = calls.clone();
+        before_each_tagged(vec!["db", "cache"], move |_| {
+            log.push("reset_db");
+            Ok(())
+        });
+        let log = calls.clone();
+        after_each_tagged(vec!["db"], move |_| {
+            log.push("check_db");
+            Ok(())
+        });
+        let log = calls.clone();
+        before_each(move |_| {
+            log.push("every_test");
+            Ok(())
+        });
+        test_with_tags("tagged_hooks_db", vec!["db", "slow"], |_| Ok(()));
+        test_with_tags("tagged_hooks_unit", vec!["unit"], |_| Ok(()));
+        test("tagged_hooks_untagged", |_| Ok(()));
+        
+        let report = run_tests_collect(TestConfig { max_concurrency, ..Default::default() });
+        assert_eq!(report.passed, 3);
+        let count = |entry: &str| calls.entries().iter().filter(|e| *e == entry).count();
+        assert_eq!((count("reset_db"), count("check_db"), count("every_test")), (1, 1, 3));
+    }
+}
+
+#[test]
+fn test_context_elapsed_measures_the_test_body() {
+    // elapsed() starts with the test body, on the runner's clock even when a timeout moves the body to another thread
+    use rust_test_harness::{run_tests_collect, test_with_timeout};
+    
+    for max_concurrency in [Some(1), Some(2)] {
+        before_each(|_| {
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        });
+        let body = |ctx: &mut rust_test_harness::TestContext| {
+            if ctx.elapsed() >= Duration::from_millis(200) {
+                return Err(format!("elapsed {:?} includes the before_each hook", ctx.elapsed()).into());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+            if ctx.elapsed() < Duration::from_millis(50) {
+                return Err(format!("elapsed {:?} after sleeping 50ms", ctx.elapsed()).into());
+            }
+            Ok(())
+        };
+        test("elapsed_untimed", body);
+        test_with_timeout("elapsed_timed", Duration::from_secs(5), body);
+        
+        let report = run_tests_collect(TestConfig { max_concurrency, ..Default::default() });
+        assert_eq!(report.passed, 2, "{:?}", report.results);
+    }
+}
+
+#[test]
+fn test_test_error_converts_with_question_mark() {
+    // TestError is a std error, and container errors convert into it with `?` inside test bodies
+    use rust_test_harness::{run_tests_collect, ContainerConfig, TestStatus};
+    
+    fn helper() -> Result<(), Box<dyn std::error::Error>> {
+        Err(TestError::Message("lookup failed".to_string()))?;
+        Ok(())
+    }
+    let err = helper().unwrap_err();
+    assert_eq!(err.to_string(), "lookup failed");
+    assert!(err.source().is_none());
+    
+    test("container_start_fails", |_| {
+        // Rejected by validation, so this fails the same way with or without Docker
+        let _container = ContainerConfig::new("alpine:3.19")
+            .volume("/no/such/host/dir/for/question_mark", "/data")
+            .start()?;
+        Ok(())
+    });
+    let report = run_tests_collect(TestConfig::default());
+    match &report.results[0].1 {
+        TestStatus::Failed(TestError::Message(msg)) => {
+            assert!(msg.starts_with("Invalid container config for alpine:3.19"), "{}", msg);
+        }
+        other => panic!("expected the start() error as a message, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_before_all_fixtures_reach_parallel_and_timed_tests() {
+    // Any type set with set_fixture in before_all is visible to every test, whichever thread runs it
+    use rust_test_harness::{run_tests_collect, test_with_timeout, SharedCounter};
+    use std::sync::Mutex;
+    
+    struct Database { rows: Vec<String> }
+    
+    before_all(|ctx| {
+        ctx.set_fixture(Mutex::new(Database { rows: Vec::new() }));
+        ctx.set_fixture(42u32);
+        Ok(())
+    });
+    for i in 0..4 {
+        test(&format!("fixture_parallel_{}", i), move |ctx| {
+            let db = ctx.fixture::<Mutex<Database>>().ok_or("database fixture missing")?;
+            db.lock().unwrap().rows.push(format!("parallel {}", i));
+            Ok(())
+        });
+    }
+    test_with_timeout("fixture_timed", Duration::from_secs(5), |ctx| {
+        let db = ctx.fixture::<Mutex<Database>>().ok_or("database fixture missing")?;
+        db.lock().unwrap().rows.push("timed".to_string());
+        Ok(())
+    });
+    test("fixture_by_type", |ctx| {
+        if ctx.fixture::<u32>().as_deref() != Some(&42) || ctx.fixture::<u64>().is_some() {
+            return Err("fixtures are looked up by type".into());
+        }
+        Ok(())
+    });
+    let rows_at_end = SharedCounter::new();
+    let rows = rows_at_end.clone();
+    after_all(move |ctx| {
+        let db = ctx.fixture::<Mutex<Database>>().ok_or("database fixture missing")?;
+        rows.add(db.lock().unwrap().rows.len());
+        Ok(())
+    });
+    
+    let report = run_tests_collect(TestConfig { max_concurrency: Some(4), ..Default::default() });
+    assert_eq!(report.passed, 6, "{:?}", report.results);
+    assert_eq!(rows_at_end.get(), 5, "every test should have written to the one shared database");
+}
+
+#[test]
+fn test_shards_cover_the_filtered_tests_once() {
+    // Every selected test runs in exactly one shard, whatever the shuffle seed
+    use rust_test_harness::{run_tests_collect, run_tests_with_config, ExitCodeMap, RunOutcome, SharedLog};
+    
+    let register = |log: &SharedLog| {
+        for i in 0..10 {
+            let log = log.clone();
+            test(&format!("sharded_{}", i), move |_| {
+                log.push(format!("sharded_{}", i));
+                Ok(())
+            });
+        }
+        let log = log.clone();
+        test("not_sharded", move |_| {
+            log.push("not_sharded");
+            Ok(())
+        });
+    };
+    
+    let ran = SharedLog::new();
+    let mut shard_sizes = Vec::new();
+    for shard_index in 0..3 {
+        register(&ran);
+        let before = ran.len();
+        let config = TestConfig {
+            filter: Some("sharded_".to_string()),
+            shuffle_seed: Some(99),
+            shard_index: Some(shard_index),
+            shard_count: Some(3),
+            max_concurrency: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(run_tests_with_config(config), 0);
+        shard_sizes.push(ran.len() - before);
+    }
+    let mut names = ran.entries();
+    names.sort();
+    let mut expected: Vec<String> = (0..10).map(|i| format!("sharded_{}", i)).collect();
+    expected.sort();
+    assert_eq!(names, expected, "the shards together run each filtered test once");
+    assert_eq!(shard_sizes, vec![4, 3, 3]);
+    
+    // An out-of-range shard is a config error, not an empty run
+    for (shard_index, shard_count) in [(Some(3), Some(3)), (Some(0), Some(0)), (Some(1), None)] {
+        let ran = SharedLog::new();
+        register(&ran);
+        let outcome = run_tests_collect(TestConfig { shard_index, shard_count, ..Default::default() }).outcome;
+        assert!(matches!(outcome, RunOutcome::InvalidConfig(_)), "{:?}", outcome);
+        assert_eq!(outcome.exit_code(&ExitCodeMap { invalid_config: 4, ..Default::default() }), 4);
+        assert!(ran.is_empty());
+    }
+}
+
+#[test]
+fn test_filter_regex_selects_by_pattern() {
+    // filter_regex keeps matching names, must agree with filter when both are set, and a bad
+    // pattern stops the run instead of being ignored
+    use rust_test_harness::{run_tests_collect, RunOutcome, SharedLog};
+    
+    let run = |filter: Option<&str>, filter_regex: &str| {
+        let ran = SharedLog::new();
+        for name in ["checkout_fast", "checkout_slow", "refund_slow", "slow_start"] {
+            let ran = ran.clone();
+            test(name, move |_| {
+                ran.push(name);
+                Ok(())
+            });
+        }
+        let config = TestConfig {
+            filter: filter.map(str::to_string),
+            filter_regex: Some(filter_regex.to_string()),
+            max_concurrency: Some(1),
+            ..Default::default()
+        };
+        let outcome = run_tests_collect(config).outcome;
+        (outcome, ran.entries())
+    };
+    
+    assert_eq!(run(None, "_slow$"), (RunOutcome::AllPassed, vec!["checkout_slow".to_string(), "refund_slow".to_string()]));
+    assert_eq!(run(None, "^(refund|slow)_"), (RunOutcome::AllPassed, vec!["refund_slow".to_string(), "slow_start".to_string()]));
+    assert_eq!(run(Some("checkout"), "_slow$"), (RunOutcome::AllPassed, vec!["checkout_slow".to_string()]));
+    
+    let (outcome, ran) = run(None, "(unclosed");
+    match outcome {
+        RunOutcome::InvalidConfig(problem) => assert!(problem.starts_with("filter_regex \"(unclosed\" is not a valid regex"), "{}", problem),
+        other => panic!("expected InvalidConfig, got {:?}", other),
+    }
+    assert!(ran.is_empty());
+}
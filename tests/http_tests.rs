@@ -0,0 +1,54 @@
+//! Tests for the built-in minimal HTTP client
+//!
+//! Each test serves one canned response from a local listener.
+
+use rust_test_harness::http;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+/// Serve `response` to the first connection and return the URL to reach it
+fn serve_once(response: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    format!("http://127.0.0.1:{}/health", port)
+}
+
+#[test]
+fn test_http_get_reads_status_headers_and_body() {
+    let url = serve_once("HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\nok");
+    
+    let response = http::get(&url, Duration::from_secs(2)).unwrap();
+    assert_eq!(response.status, 200);
+    assert!(response.is_success());
+    assert_eq!(response.header("content-type"), Some("text/plain"));
+    assert_eq!(response.body, "ok");
+}
+
+#[test]
+fn test_http_get_decodes_chunked_body() {
+    let url = serve_once("HTTP/1.1 503 Service Unavailable\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nstart\r\n3\r\ning\r\n0\r\n\r\n");
+    
+    let response = http::get(&url, Duration::from_secs(2)).unwrap();
+    assert_eq!(response.status, 503);
+    assert!(!response.is_success());
+    assert_eq!(response.body, "starting");
+}
+
+#[test]
+fn test_http_get_reports_errors() {
+    // Only plain http:// URLs are supported
+    assert!(http::get("https://localhost/", Duration::from_secs(1)).is_err());
+    
+    // Nothing listening on the port
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let err = http::get(&format!("http://127.0.0.1:{}/", port), Duration::from_secs(1)).unwrap_err();
+    assert!(err.to_string().contains("connect failed"), "unexpected error: {}", err);
+}
@@ -64,6 +64,7 @@ fn test_integration_with_config() {
         html_report: None,
         skip_hooks: None,
         timeout_config: TimeoutConfig::default(),
+        ..Default::default()
     };
     
     test("config_test_1", |_| Ok(()));
@@ -0,0 +1,48 @@
+//! Tests for Ctrl-C handling with `report_on_interrupt`
+//!
+//! Interrupt state is process-wide, so these live in their own test binary
+//! where no other harness run can be affected by the signal.
+
+use rust_test_harness::{test, run_tests_with_config, TestConfig};
+use std::fs;
+use std::time::Duration;
+
+#[cfg(unix)]
+#[test]
+fn test_interrupt_stops_scheduling_and_still_writes_report() {
+    println!("🧪 Testing report_on_interrupt...");
+
+    test("a_sends_interrupt", |_| {
+        let status = std::process::Command::new("kill")
+            .args(["-INT", &std::process::id().to_string()])
+            .status()
+            .map_err(|e| e.to_string())?;
+        assert!(status.success());
+        // Give the handler thread time to observe the signal
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    });
+    test("b_never_starts", |_| Err("should not run after Ctrl-C".into()));
+
+    let config = TestConfig {
+        report_on_interrupt: true,
+        max_concurrency: Some(1),
+        html_report: Some("test_interrupted_report.html".to_string()),
+        ..Default::default()
+    };
+
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 130, "Interrupted runs exit with the SIGINT convention");
+
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_interrupted_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    assert!(html_content.contains("a_sends_interrupt"));
+    assert!(html_content.contains("run interrupted"), "Unstarted tests are marked as interrupted");
+    assert!(!html_content.contains("should not run after Ctrl-C"));
+
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+
+    println!("✅ report_on_interrupt test passed");
+}
@@ -0,0 +1,171 @@
+//! Tests for what the harness logs: run summaries and diagnostics that only surface as log lines
+
+use rust_test_harness::{before_each, run_tests_collect, test, test_with_timeout, DockerHandle, TestConfig, TimeoutConfig, TimeoutStrategy};
+use std::sync::{Mutex, MutexGuard, Once};
+
+/// Every message logged in this test binary, in order
+static LOGGED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// Tests here share the logger and read process-wide state, so they run one at a time
+static SERIAL: Mutex<()> = Mutex::new(());
+
+struct RecordingLogger;
+
+impl log::Log for RecordingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+    
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            LOGGED.lock().unwrap().push(record.args().to_string());
+        }
+    }
+    
+    fn flush(&self) {}
+}
+
+/// Install the recording logger, take the serial lock and forget earlier messages
+fn start_recording() -> MutexGuard<'static, ()> {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        log::set_logger(&RecordingLogger).expect("no other logger is installed in this binary");
+        log::set_max_level(log::LevelFilter::Info);
+    });
+    let serial = SERIAL.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    LOGGED.lock().unwrap().clear();
+    serial
+}
+
+fn logged() -> Vec<String> {
+    LOGGED.lock().unwrap().clone()
+}
+
+#[test]
+fn test_fail_fast_summary_counts_deselected_tests() {
+    let _serial = start_recording();
+    
+    test("ffd_pass", |_| Ok(()));
+    test("ffd_fail", |_| Err("first failure".into()));
+    test("ffd_after", |_| Ok(()));
+    test("other_deselected_1", |_| Ok(()));
+    test("other_deselected_2", |_| Ok(()));
+    
+    let report = run_tests_collect(TestConfig {
+        filter: Some("ffd".to_string()),
+        fail_fast: true,
+        max_concurrency: Some(1),
+        ..Default::default()
+    });
+    assert_eq!((report.passed, report.failed), (1, 1));
+    
+    let lines = logged();
+    assert!(lines.iter().any(|line| line == "  not run after the first failure (fail_fast): 1"), "{:#?}", lines);
+    assert!(lines.iter().any(|line| line == "  not run in this selection: 2"), "{:#?}", lines);
+}
+
+#[test]
+fn test_thread_leak_is_logged_with_the_test_name() {
+    let _serial = start_recording();
+    
+    test("leaks_a_thread", |_| {
+        std::thread::spawn(|| std::thread::sleep(std::time::Duration::from_millis(500)));
+        Ok(())
+    });
+    test("joins_its_thread", |_| {
+        std::thread::spawn(|| ()).join().unwrap();
+        Ok(())
+    });
+    
+    let report = run_tests_collect(TestConfig {
+        detect_thread_leaks: true,
+        max_concurrency: Some(1),
+        ..Default::default()
+    });
+    assert_eq!(report.passed, 2, "a leak is a warning, not a failure");
+    
+    let lines = logged();
+    let leaks: Vec<&String> = lines.iter().filter(|line| line.starts_with("🧵 Test ")).collect();
+    assert_eq!(leaks.len(), 1, "{:#?}", lines);
+    assert!(leaks[0].starts_with("🧵 Test 'leaks_a_thread' leaked 1 thread(s) ("), "{}", leaks[0]);
+}
+
+fn docker_handle(container_id: &str) -> Option<DockerHandle> {
+    Some(DockerHandle { container_id: container_id.to_string(), ports: Vec::new() })
+}
+
+#[test]
+fn test_timed_out_test_still_cleans_up_its_container() {
+    let _serial = start_recording();
+    
+    test_with_timeout("times_out_with_container", std::time::Duration::from_millis(50), |ctx| {
+        ctx.docker_handle = docker_handle("timed-out-container");
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        Ok(())
+    });
+    let report = run_tests_collect(TestConfig {
+        timeout_config: TimeoutConfig { strategy: TimeoutStrategy::Aggressive },
+        max_concurrency: Some(1),
+        ..Default::default()
+    });
+    assert_eq!(report.failed, 1);
+    
+    // The worker outlives the timeout, so the cleanup happens once it finishes
+    let cleaned = "🧹 Cleaning up Docker container: timed-out-container".to_string();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while !logged().contains(&cleaned) && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert!(logged().contains(&cleaned), "{:#?}", logged());
+}
+
+#[test]
+fn test_timed_test_keeps_the_hook_container_handle() {
+    let _serial = start_recording();
+    
+    before_each(|ctx| {
+        ctx.docker_handle = docker_handle("hook-container");
+        Ok(())
+    });
+    test_with_timeout("timed_with_container", std::time::Duration::from_secs(5), |ctx| {
+        ctx.docker_handle = docker_handle("test-container");
+        Ok(())
+    });
+    let report = run_tests_collect(TestConfig {
+        max_concurrency: Some(1),
+        skip_hooks: Some(false),
+        ..Default::default()
+    });
+    assert_eq!(report.passed, 1);
+    
+    let lines = logged();
+    for container in ["hook-container", "test-container"] {
+        let cleaned = format!("🧹 Cleaning up Docker container: {}", container);
+        assert!(lines.contains(&cleaned), "{} should be cleaned up: {:#?}", container, lines);
+    }
+}
+
+#[test]
+fn test_unrecognized_baseline_is_rejected() {
+    let _serial = start_recording();
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let baseline_path = format!("{}/test-reports/test_unrecognized_baseline.txt", target_dir);
+    std::fs::create_dir_all(format!("{}/test-reports", target_dir)).unwrap();
+    std::fs::write(&baseline_path, "PASS baseline_text_report <100ms\n").unwrap();
+    
+    test("baseline_text_report", |_| Err("broken".into()));
+    let report = run_tests_collect(TestConfig {
+        baseline: Some(baseline_path.clone().into()),
+        baseline_new_failures_only: true,
+        ..Default::default()
+    });
+    assert_eq!(report.failed, 1, "without a usable baseline every failure counts");
+    assert_eq!(report.outcome.to_exit_code(), 1);
+    
+    let lines = logged();
+    let expected = format!("⚠️  Failed to read baseline {}: line 1 is not an NDJSON result; the baseline must be an ndjson_stream or json_report file", baseline_path);
+    assert!(lines.contains(&expected), "{:#?}", lines);
+    
+    let _ = std::fs::remove_file(&baseline_path);
+}
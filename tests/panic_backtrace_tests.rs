@@ -0,0 +1,40 @@
+//! Tests for the panic location and backtrace in a panicking test's error
+//!
+//! These set RUST_BACKTRACE, which is process-wide, so they live in their own test binary where
+//! nothing else reads it while it changes
+
+use rust_test_harness::{run_tests_collect, test, test_with_timeout, TestConfig, TestError, TestStatus};
+use std::time::Duration;
+
+#[test]
+fn test_panics_report_location_and_backtrace() {
+    // A panicking test's error says where it panicked, on the runner's thread and on a timeout worker,
+    // and carries a backtrace only when RUST_BACKTRACE asks for one
+    let panic_errors = || {
+        test("panics_untimed", |_| panic!("untimed boom"));
+        test_with_timeout("panics_timed", Duration::from_secs(5), |_| panic!("timed boom"));
+        let report = run_tests_collect(TestConfig { max_concurrency: Some(1), ..Default::default() });
+        report.results.into_iter().map(|(name, status, _)| match status {
+            TestStatus::Failed(TestError::Panicked(msg)) => (name, msg),
+            other => panic!("{} should have panicked, got {:?}", name, other),
+        }).collect::<Vec<_>>()
+    };
+    
+    let previous = std::env::var_os("RUST_BACKTRACE");
+    std::env::set_var("RUST_BACKTRACE", "0");
+    for (name, msg) in panic_errors() {
+        let expected = if name == "panics_untimed" { "untimed boom" } else { "timed boom" };
+        assert!(msg.starts_with(&format!("{}\n  at tests/panic_backtrace_tests.rs:", expected)), "{}: {}", name, msg);
+        assert!(!msg.contains("stack backtrace:"), "{}: {}", name, msg);
+    }
+    
+    std::env::set_var("RUST_BACKTRACE", "1");
+    let errors = panic_errors();
+    match previous {
+        Some(value) => std::env::set_var("RUST_BACKTRACE", value),
+        None => std::env::remove_var("RUST_BACKTRACE"),
+    }
+    for (name, msg) in errors {
+        assert!(msg.contains("\n\nstack backtrace:\n"), "{}: {}", name, msg);
+    }
+}
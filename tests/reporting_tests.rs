@@ -1,5 +1,5 @@
 use rust_test_harness::{
-    test, run_tests_with_config, TestConfig
+    test, test_with_tags, test_annotated, before_all, run_tests_with_config, TestConfig
 };
 use std::time::Duration;
 use std::fs;
@@ -635,4 +635,1143 @@ fn test_html_report_generation_performance() {
     
     // Cleanup
     let _ = fs::remove_file(&html_path);
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_ndjson_stream_one_line_per_test() {
+    // Test that the NDJSON stream writes one JSON object per finished test
+    
+    test("ndjson_passing_test", |_| Ok(()));
+    test("ndjson_failing_test", |_| Err("ndjson \"quoted\" failure".into()));
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_ndjson_stream.ndjson", target_dir);
+    
+    let config = TestConfig {
+        ndjson_stream: Some(stream_path.clone().into()),
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 1);
+    
+    let content = fs::read_to_string(&stream_path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 2, "NDJSON stream should contain one line per test");
+    
+    let passing = lines.iter().find(|l| l.contains("\"ndjson_passing_test\"")).unwrap();
+    assert!(passing.contains("\"status\":\"passed\""));
+    assert!(passing.contains("\"duration_ms\":"));
+    assert!(passing.contains("\"error\":null"));
+    
+    let failing = lines.iter().find(|l| l.contains("\"ndjson_failing_test\"")).unwrap();
+    assert!(failing.contains("\"status\":\"failed\""));
+    assert!(failing.contains("\"error\":\"ndjson \\\"quoted\\\" failure\""), "error should be JSON-escaped: {}", failing);
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+}
+
+#[test]
+fn test_before_all_metadata_in_reports() {
+    // Test that metadata set in before_all shows up in the NDJSON stream and the HTML, JUnit and JSON reports
+    use rust_test_harness::SharedLog;
+    
+    let run_ids = SharedLog::new();
+    let run_ids_in_hook = run_ids.clone();
+    before_all(move |ctx| {
+        run_ids_in_hook.push(ctx.run_id());
+        ctx.set_report_metadata("postgres", "13.4");
+        ctx.set_report_metadata("seed", "998");
+        ctx.set_report_metadata("seed", "999");
+        Ok(())
+    });
+    test("metadata_test", |_| Ok(()));
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_report_metadata.ndjson", target_dir);
+    let html_path = format!("{}/test-reports/test_report_metadata.html", target_dir);
+    let junit_path = format!("{}/test-reports/test_report_metadata.xml", target_dir);
+    let json_path = format!("{}/test-reports/test_report_metadata.json", target_dir);
+    
+    let config = TestConfig {
+        ndjson_stream: Some(stream_path.clone().into()),
+        html_report: Some("test_report_metadata.html".to_string()),
+        junit_report: Some("test_report_metadata.xml".to_string()),
+        #[cfg(feature = "serde_json")]
+        json_report: Some("test_report_metadata.json".to_string()),
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 0);
+    
+    let content = fs::read_to_string(&stream_path).unwrap();
+    let environment = format!("{{\"environment\":{{\"postgres\":\"13.4\",\"seed\":\"999\"}},\"run_id\":\"{}\"}}", run_ids.entries()[0]);
+    assert_eq!(content.lines().next(), Some(environment.as_str()));
+    assert_eq!(content.lines().count(), 2);
+    
+    let html = fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("🌍 Environment"));
+    assert!(html.contains(r#"<div class="metadata-label">postgres</div><div class="metadata-value">13.4</div>"#));
+    
+    // The suite's properties list the run id first, then the metadata in the order it was set
+    let junit = fs::read_to_string(&junit_path).unwrap();
+    let suite_properties = junit.split("<testcase ").next().unwrap();
+    assert!(suite_properties.contains("  <properties>\n    <property name=\"run_id\" value=\""), "{}", junit);
+    assert!(suite_properties.contains("\"/>\n    <property name=\"postgres\" value=\"13.4\"/>\n    <property name=\"seed\" value=\"999\"/>\n  </properties>\n"), "{}", junit);
+    
+    #[cfg(feature = "serde_json")]
+    {
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(report["environment"]["postgres"], "13.4");
+        assert_eq!(report["environment"]["seed"], "999");
+        assert!(report["environment"]["run_id"].is_string());
+    }
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+    let _ = fs::remove_file(&html_path);
+    let _ = fs::remove_file(&junit_path);
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn test_runtime_tags_in_ndjson_stream() {
+    // Test that tags added while a test runs are written back into its result,
+    // including from the worker thread that runs tests with a timeout
+    
+    rust_test_harness::test_with_tags("runtime_tag_test", vec!["db"], |ctx| {
+        ctx.add_tag("slow-path");
+        ctx.add_tag("db");
+        Ok(())
+    });
+    rust_test_harness::test_with_timeout("runtime_tag_timeout_test", Duration::from_secs(5), |ctx| {
+        ctx.add_tag("retried");
+        Ok(())
+    });
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_runtime_tags.ndjson", target_dir);
+    
+    let config = TestConfig {
+        ndjson_stream: Some(stream_path.clone().into()),
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 0);
+    
+    let content = fs::read_to_string(&stream_path).unwrap();
+    let tagged = content.lines().find(|l| l.contains("\"runtime_tag_test\"")).unwrap();
+    assert!(tagged.contains("\"tags\":[\"db\",\"slow-path\"]"), "runtime tags should follow registered ones: {}", tagged);
+    let timed = content.lines().find(|l| l.contains("\"runtime_tag_timeout_test\"")).unwrap();
+    assert!(timed.contains("\"tags\":[\"retried\"]"), "{}", timed);
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+}
+
+#[test]
+fn test_baseline_only_fails_on_new_failures() {
+    // Test that failures already present in the baseline don't fail the run
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let baseline_path = format!("{}/test-reports/test_baseline.ndjson", target_dir);
+    fs::create_dir_all(format!("{}/test-reports", target_dir)).unwrap();
+    fs::write(&baseline_path, concat!(
+        "{\"name\":\"baseline_known_broken\",\"status\":\"failed\",\"duration_ms\":1,\"error\":\"boom\"}\n",
+        "{\"name\":\"baseline_was_green\",\"status\":\"passed\",\"duration_ms\":1,\"error\":null}\n",
+        "{\"name\":\"baseline_now_fixed\",\"status\":\"failed\",\"duration_ms\":1,\"error\":\"boom\"}\n",
+    )).unwrap();
+    let config = || TestConfig {
+        baseline: Some(baseline_path.clone().into()),
+        baseline_new_failures_only: true,
+        ..Default::default()
+    };
+    
+    test("baseline_known_broken", |_| Err("still broken".into()));
+    test("baseline_was_green", |_| Ok(()));
+    test("baseline_now_fixed", |_| Ok(()));
+    assert_eq!(run_tests_with_config(config()), 0, "Known failures are tolerated");
+    
+    test("baseline_known_broken", |_| Err("still broken".into()));
+    test("baseline_was_green", |_| Err("regressed".into()));
+    assert_eq!(run_tests_with_config(config()), 1, "A test that passed in the baseline now fails");
+    
+    // Only the new failures decide whether the run counts as timed out
+    test("baseline_known_broken", |_| Err("still broken".into()));
+    rust_test_harness::test_with_timeout("baseline_was_green", Duration::from_millis(20), |_| {
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    });
+    assert_eq!(rust_test_harness::run_tests_with_outcome(config()), rust_test_harness::RunOutcome::TimedOut(1));
+    
+    // Without the gate, every failure still counts
+    test("baseline_known_broken", |_| Err("still broken".into()));
+    let config = TestConfig {
+        baseline: Some(baseline_path.clone().into()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    // Cleanup
+    let _ = fs::remove_file(&baseline_path);
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_baseline_from_json_report() {
+    // Test that a previous run's JSON report works as the baseline, timeouts counting as failures
+    use rust_test_harness::test_with_timeout;
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let baseline_path = format!("{}/test-reports/test_json_baseline.json", target_dir);
+    
+    test("json_baseline_broken", |_| Err("broken".into()));
+    test_with_timeout("json_baseline_slow", Duration::from_millis(20), |_| {
+        std::thread::sleep(Duration::from_millis(200));
+        Ok(())
+    });
+    test("json_baseline_green", |_| Ok(()));
+    let config = TestConfig {
+        json_report: Some("test_json_baseline.json".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    let config = || TestConfig {
+        baseline: Some(baseline_path.clone().into()),
+        baseline_new_failures_only: true,
+        ..Default::default()
+    };
+    test("json_baseline_broken", |_| Err("still broken".into()));
+    test("json_baseline_slow", |_| Err("still slow".into()));
+    test("json_baseline_green", |_| Ok(()));
+    assert_eq!(run_tests_with_config(config()), 0, "Failures and timeouts from the JSON report are known");
+    
+    test("json_baseline_green", |_| Err("regressed".into()));
+    assert_eq!(run_tests_with_config(config()), 1);
+    
+    // Cleanup
+    let _ = fs::remove_file(&baseline_path);
+}
+
+#[test]
+fn test_text_report_is_byte_stable() {
+    // Test that the text report is sorted by name and identical across runs
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let report_path = format!("{}/test-reports/test_text_report.txt", target_dir);
+    
+    let run = |path: &str| {
+        test("text_report_zeta", |_| Ok(()));
+        test("text_report_alpha", |_| {
+            std::thread::sleep(Duration::from_millis(5));
+            Ok(())
+        });
+        test("text_report_skipped", |ctx| ctx.skip("not today"));
+        let config = TestConfig {
+            text_report: Some(path.into()),
+            ..Default::default()
+        };
+        assert_eq!(run_tests_with_config(config), 0);
+        fs::read_to_string(path).unwrap()
+    };
+    
+    let first = run(&report_path);
+    let second = run(&report_path);
+    assert_eq!(first, second, "Passing suites produce identical reports");
+    assert_eq!(first, "PASS text_report_alpha <100ms\nSKIP text_report_skipped <100ms\nPASS text_report_zeta <100ms\n\n3 tests: 2 passed, 0 failed, 1 skipped\n");
+    
+    // Cleanup
+    let _ = fs::remove_file(&report_path);
+}
+
+#[test]
+fn test_annotations_are_reported() {
+    // Test that test_annotated metadata ends up in the NDJSON stream and the HTML, JUnit and JSON reports
+    
+    test_annotated("annotated_refund_test", &[("ticket", "PAY-123"), ("owner", "payments")], |_| Ok(()));
+    test("unannotated_test", |_| Ok(()));
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_annotations.ndjson", target_dir);
+    let config = TestConfig {
+        ndjson_stream: Some(stream_path.clone().into()),
+        html_report: Some("test_annotations_report.html".to_string()),
+        junit_report: Some("test_annotations_report.xml".to_string()),
+        #[cfg(feature = "serde_json")]
+        json_report: Some("test_annotations_report.json".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+    
+    // Sorted by key in both reports
+    let content = fs::read_to_string(&stream_path).unwrap();
+    let refund = content.lines().find(|l| l.contains("\"annotated_refund_test\"")).unwrap();
+    assert!(refund.contains("\"annotations\":{\"owner\":\"payments\",\"ticket\":\"PAY-123\"}"), "{}", refund);
+    let plain = content.lines().find(|l| l.contains("\"unannotated_test\"")).unwrap();
+    assert!(plain.contains("\"annotations\":{}"));
+    
+    let html_path = format!("{}/test-reports/test_annotations_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    assert!(html_content.contains(r#"<div class="metadata-label">owner</div><div class="metadata-value">payments</div></div><div class="metadata-item"><div class="metadata-label">ticket</div><div class="metadata-value">PAY-123</div>"#));
+    
+    let junit_path = format!("{}/test-reports/test_annotations_report.xml", target_dir);
+    let junit_content = fs::read_to_string(&junit_path).unwrap();
+    assert!(junit_content.contains(r#"<testcase name="annotated_refund_test""#), "{}", junit_content);
+    assert!(junit_content.contains("    <properties>\n      <property name=\"owner\" value=\"payments\"/>\n      <property name=\"ticket\" value=\"PAY-123\"/>\n    </properties>\n"), "{}", junit_content);
+    assert_eq!(junit_content.matches("    <properties>").count(), 1, "Unannotated tests get no properties");
+    
+    let json_path = format!("{}/test-reports/test_annotations_report.json", target_dir);
+    #[cfg(feature = "serde_json")]
+    {
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        let tests = report["tests"].as_array().unwrap();
+        let by_name = |name: &str| tests.iter().find(|t| t["name"] == name).unwrap().clone();
+        assert_eq!(by_name("annotated_refund_test")["annotations"], serde_json::json!({ "owner": "payments", "ticket": "PAY-123" }));
+        assert_eq!(by_name("unannotated_test")["annotations"], serde_json::json!({}));
+    }
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+    let _ = fs::remove_file(&html_path);
+    let _ = fs::remove_file(&junit_path);
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn test_covers_declarations_are_reported() {
+    // Test that files declared with ctx.covers end up in the NDJSON stream and the HTML, JUnit and JSON reports
+    
+    test("covers_auth_test", |ctx| {
+        ctx.covers("src/auth.rs");
+        ctx.covers("src/session.rs");
+        ctx.covers("src/auth.rs");
+        Ok(())
+    });
+    test("covers_nothing_test", |_| Ok(()));
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_covers.ndjson", target_dir);
+    let config = TestConfig {
+        ndjson_stream: Some(stream_path.clone().into()),
+        html_report: Some("test_covers_report.html".to_string()),
+        junit_report: Some("test_covers_report.xml".to_string()),
+        #[cfg(feature = "serde_json")]
+        json_report: Some("test_covers_report.json".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+    
+    let content = fs::read_to_string(&stream_path).unwrap();
+    let auth = content.lines().find(|l| l.contains("\"covers_auth_test\"")).unwrap();
+    assert!(auth.contains("\"covers\":[\"src/auth.rs\",\"src/session.rs\"]"), "duplicates are dropped: {}", auth);
+    let nothing = content.lines().find(|l| l.contains("\"covers_nothing_test\"")).unwrap();
+    assert!(nothing.contains("\"covers\":[]"));
+    
+    let html_path = format!("{}/test-reports/test_covers_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    assert!(html_content.contains("Covers"));
+    assert!(html_content.contains("src/auth.rs, src/session.rs"));
+    
+    let junit_path = format!("{}/test-reports/test_covers_report.xml", target_dir);
+    let junit_content = fs::read_to_string(&junit_path).unwrap();
+    assert!(junit_content.contains("    <properties>\n      <property name=\"covers\" value=\"src/auth.rs\"/>\n      <property name=\"covers\" value=\"src/session.rs\"/>\n    </properties>\n"), "{}", junit_content);
+    assert_eq!(junit_content.matches("    <properties>").count(), 1, "Tests that cover nothing get no properties");
+    
+    let json_path = format!("{}/test-reports/test_covers_report.json", target_dir);
+    #[cfg(feature = "serde_json")]
+    {
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        let tests = report["tests"].as_array().unwrap();
+        let by_name = |name: &str| tests.iter().find(|t| t["name"] == name).unwrap().clone();
+        assert_eq!(by_name("covers_auth_test")["covers"], serde_json::json!(["src/auth.rs", "src/session.rs"]));
+        assert_eq!(by_name("covers_nothing_test")["covers"], serde_json::json!([]));
+    }
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+    let _ = fs::remove_file(&html_path);
+    let _ = fs::remove_file(&junit_path);
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn test_html_report_includes_captured_logs() {
+    // Test that log records emitted inside a test are attributed to it in the report
+    
+    test("logging_test", |_| {
+        log::warn!(target: "app_under_test", "connected to <primary> replica");
+        Ok(())
+    });
+    test("quiet_test", |_| Ok(()));
+    
+    let config = TestConfig {
+        html_report: Some("test_captured_logs_report.html".to_string()),
+        capture_logs: true,
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 0);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_captured_logs_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    
+    // The log line is HTML-escaped and shown exactly once, under the test that emitted it
+    assert_eq!(html_content.matches("connected to &lt;primary&gt; replica").count(), 1);
+    assert!(html_content.contains("[WARN app_under_test]"), "HTML should show level and target");
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_html_report_truncates_long_errors() {
+    // Test that oversized failure messages are cut down in the report with a marker
+    
+    test("huge_error_test", |_| {
+        Err(format!("start{}end", "x".repeat(500)).into())
+    });
+    
+    let config = TestConfig {
+        html_report: Some("test_truncated_error_report.html".to_string()),
+        max_error_len: Some(64),
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 1);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_truncated_error_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    
+    assert!(html_content.contains("start"), "HTML should keep the start of the error");
+    assert!(!html_content.contains("xend"), "HTML should drop the tail of the error");
+    assert!(html_content.contains("... (truncated, 508 bytes total)"), "HTML should mark the truncation");
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_truncated_error_kept_in_full_with_capture() {
+    // Test that a truncated failure message is saved in full and attached when capture is on
+    
+    test("huge_captured_error_test", |_| {
+        Err(format!("start{}end", "x".repeat(500)).into())
+    });
+    test("short_captured_error_test", |_| {
+        Err("short".into())
+    });
+    
+    let config = TestConfig {
+        html_report: Some("test_full_error_report.html".to_string()),
+        max_error_len: Some(64),
+        capture_logs: true,
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 1);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_full_error_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    let error_path = format!("{}/test-reports/errors/huge_captured_error_test.txt", target_dir);
+    
+    assert_eq!(fs::read_to_string(&error_path).unwrap(), format!("start{}end", "x".repeat(500)), "The full message should be saved");
+    assert_eq!(html_content.matches("full_error").count(), 1, "Only the truncated error should be attached");
+    assert!(html_content.contains(&error_path), "HTML should point at the saved message");
+    assert!(!Path::new(&format!("{}/test-reports/errors/short_captured_error_test.txt", target_dir)).exists());
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+    let _ = fs::remove_file(&error_path);
+}
+
+#[test]
+fn test_full_errors_of_similar_names_get_their_own_files() {
+    // Test that names which sanitize to the same file name don't overwrite each other's error
+    
+    for name in ["clashing/error", "clashing_error"] {
+        test(name, move |_| Err(format!("{}{}", name, "x".repeat(500)).into()));
+    }
+    
+    let config = TestConfig {
+        max_error_len: Some(64),
+        capture_logs: true,
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let error_dir = format!("{}/test-reports/errors", target_dir);
+    let saved: Vec<_> = fs::read_dir(&error_dir).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.file_name().unwrap().to_str().unwrap().starts_with("clashing_error"))
+        .collect();
+    let mut contents: Vec<_> = saved.iter().map(|path| fs::read_to_string(path).unwrap()).collect();
+    contents.sort();
+    assert_eq!(contents, vec![
+        format!("clashing/error{}", "x".repeat(500)),
+        format!("clashing_error{}", "x".repeat(500)),
+    ]);
+    assert_eq!(fs::read_to_string(format!("{}/clashing_error.txt", error_dir)).unwrap(), contents[1], "Safe names keep their plain file name");
+    
+    // Cleanup
+    for path in saved {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[test]
+fn test_html_report_marks_expected_durations() {
+    // Test that tests with an expected duration show whether they stayed within budget
+    use rust_test_harness::test_with_expected_duration;
+    
+    test_with_expected_duration("within_budget_test", Duration::from_secs(60), |_| Ok(()));
+    test_with_expected_duration("over_budget_test", Duration::from_millis(1), |_| {
+        std::thread::sleep(Duration::from_millis(20));
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        html_report: Some("test_expected_duration_report.html".to_string()),
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 0, "Exceeding an expected duration is reported, not failed");
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_expected_duration_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    
+    assert_eq!(html_content.matches("Expected Duration</div>").count(), 1, "Only the in-budget test is unflagged");
+    assert_eq!(html_content.matches("Expected Duration (exceeded)").count(), 1, "The over-budget test is flagged");
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_html_report_collapses_long_logs() {
+    // Test that large captured logs are shown in a collapsible block with a "show more" toggle
+    
+    test("chatty_failing_test", |_| {
+        for i in 0..60 {
+            log::info!(target: "app_under_test", "chatty line {}", i);
+        }
+        Err("failed after logging".into())
+    });
+    
+    let config = TestConfig {
+        html_report: Some("test_long_logs_report.html".to_string()),
+        capture_logs: true,
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 1);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_long_logs_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    
+    // Failed tests open their logs; the overflow is hidden but still in the page
+    assert!(html_content.contains(r#"<details class="test-logs" open><summary>Logs (60 lines)</summary>"#));
+    assert!(html_content.contains("Show 10 more lines"));
+    assert!(html_content.contains("chatty line 59"));
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_html_report_groups_by_tag_namespace() {
+    // Test that report_group_by puts tests into sections with per-section counts
+    
+    test_with_tags("group_auth_login", vec!["fast", "suite:auth"], |_| Ok(()));
+    test_with_tags("group_billing_charge", vec!["suite:billing"], |_| Err("declined".into()));
+    test_with_tags("group_auth_logout", vec!["suite:auth"], |_| Ok(()));
+    test("group_untagged", |_| Ok(()));
+    
+    let config = TestConfig {
+        html_report: Some("test_grouped_report.html".to_string()),
+        report_group_by: Some("suite".to_string()),
+        ..Default::default()
+    };
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 1);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_grouped_report.html", target_dir);
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    
+    // Only the section with a failure starts expanded
+    assert_eq!(html_content.matches(r#"<details class="test-group">"#).count(), 2);
+    assert_eq!(html_content.matches(r#"<details class="test-group" open>"#).count(), 1);
+    assert!(html_content.contains(r#"<span class="group-name">auth</span> <span class="group-counts"><span class="passed">2 passed</span> · <span class="failed">0 failed</span>"#));
+    assert!(html_content.contains(r#"<span class="group-name">billing</span> <span class="group-counts"><span class="passed">0 passed</span> · <span class="failed">1 failed</span>"#));
+    
+    // Sections follow registration order, with untagged tests last
+    let auth = html_content.find(r#"<span class="group-name">auth</span>"#).unwrap();
+    let billing = html_content.find(r#"<span class="group-name">billing</span>"#).unwrap();
+    let none = html_content.find(r#"<span class="group-name">(none)</span>"#).unwrap();
+    assert!(auth < billing && billing < none);
+    let logout = html_content.find(r#"data-test-name="group_auth_logout""#).unwrap();
+    assert!(logout < billing, "Both auth tests are in the auth section");
+    
+    // The search hides sections without matches, which needs a rule to take effect
+    assert!(html_content.contains(".test-item.hidden, .test-group.hidden { display: none; }"));
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_live_html_report_shows_progress() {
+    // Test that live_html rewrites the report while the run is still going
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_live_report.html", target_dir);
+    
+    test("live_first", |_| Ok(()));
+    let path = html_path.clone();
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+    let seen_clone = seen.clone();
+    test("live_second", move |_| {
+        *seen_clone.lock().unwrap() = fs::read_to_string(&path).unwrap();
+        Ok(())
+    });
+    
+    let config = TestConfig {
+        html_report: Some("test_live_report.html".to_string()),
+        live_html: true,
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+    
+    // The first update (the first test starting) is written straight away; later ones within
+    // LIVE_HTML_INTERVAL wait for the next write
+    let live = seen.lock().unwrap().clone();
+    assert!(live.contains("Run in progress"));
+    assert!(live.contains("http-equiv=\"refresh\""));
+    assert!(live.contains("live_first"));
+    assert!(live.contains("RUNNING"));
+    
+    // The final report replaces the live one
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    assert!(!html_content.contains("Run in progress"));
+    assert!(!html_content.contains("RUNNING"));
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_run_id_ties_hooks_tests_and_reports_together() {
+    // Test that every hook and test of one run sees the same run id, which the reports carry too
+    use rust_test_harness::SharedLog;
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_run_id.ndjson", target_dir);
+    let html_path = format!("{}/test-reports/test_run_id.html", target_dir);
+    let seen = SharedLog::new();
+    
+    let mut run_ids = Vec::new();
+    for _ in 0..2 {
+        let log = seen.clone();
+        before_all(move |ctx| {
+            log.push(ctx.run_id());
+            Ok(())
+        });
+        let log = seen.clone();
+        test("run_id_test", move |ctx| {
+            log.push(ctx.run_id());
+            Ok(())
+        });
+        let log = seen.clone();
+        rust_test_harness::test_with_timeout("run_id_timeout_test", Duration::from_secs(5), move |ctx| {
+            log.push(ctx.run_id());
+            Ok(())
+        });
+        
+        let config = TestConfig {
+            ndjson_stream: Some(stream_path.clone().into()),
+            html_report: Some("test_run_id.html".to_string()),
+            max_concurrency: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(run_tests_with_config(config), 0);
+        
+        let entries = seen.entries();
+        seen.clear();
+        assert_eq!(entries.len(), 3);
+        let run_id = entries[0].clone();
+        assert!(entries.iter().all(|id| *id == run_id), "{:?}", entries);
+        assert!(run_id.starts_with(&format!("{}-", rust_test_harness::process_run_id())), "{}", run_id);
+        
+        let content = fs::read_to_string(&stream_path).unwrap();
+        assert!(content.lines().all(|l| l.ends_with(&format!(",\"run_id\":\"{}\"}}", run_id))), "{}", content);
+        let html = fs::read_to_string(&html_path).unwrap();
+        assert!(html.contains(&format!(r#"<div class="metadata-label">run_id</div><div class="metadata-value">{}</div>"#, run_id)));
+        run_ids.push(run_id);
+    }
+    assert_ne!(run_ids[0], run_ids[1], "Each run gets its own id");
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_fail_on_stderr_fails_tests_that_log() {
+    // Test that a passing test which logged is failed with its output, unless it allowed that
+    
+    test("stderr_noisy_test", |_| {
+        log::error!(target: "app_under_test", "could not reach cache, falling back");
+        Ok(())
+    });
+    test("stderr_allowed_test", |ctx| {
+        ctx.allow_stderr();
+        log::warn!(target: "app_under_test", "expected deprecation warning");
+        Ok(())
+    });
+    rust_test_harness::test_with_timeout("stderr_allowed_timeout_test", Duration::from_secs(5), |ctx| {
+        ctx.allow_stderr();
+        log::warn!(target: "app_under_test", "expected warning on the worker thread");
+        Ok(())
+    });
+    test("stderr_quiet_test", |_| Ok(()));
+    let attempts = rust_test_harness::SharedCounter::new();
+    rust_test_harness::test_with_retries("stderr_noisy_flaky_test", 1, move |_| {
+        if attempts.increment() == 1 {
+            return Err("flaked".into());
+        }
+        log::warn!(target: "app_under_test", "retry succeeded after reconnecting");
+        Ok(())
+    });
+    let attempts = rust_test_harness::SharedCounter::new();
+    rust_test_harness::test_with_retries("stderr_quiet_after_retry_test", 1, move |_| {
+        if attempts.increment() == 1 {
+            log::warn!(target: "app_under_test", "first attempt lost the connection");
+            return Err("flaked".into());
+        }
+        Ok(())
+    });
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_fail_on_stderr.ndjson", target_dir);
+    let html_path = format!("{}/test-reports/test_fail_on_stderr.html", target_dir);
+    let config = TestConfig {
+        fail_on_stderr: true,
+        ndjson_stream: Some(stream_path.clone().into()),
+        html_report: Some("test_fail_on_stderr.html".to_string()),
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    let content = fs::read_to_string(&stream_path).unwrap();
+    let flaky = content.lines().find(|l| l.contains("\"stderr_noisy_flaky_test\"")).unwrap();
+    assert!(flaky.contains("\"status\":\"failed\""), "a flaky pass that logged fails too: {}", flaky);
+    assert!(!flaky.contains("\\n--- attempt"), "only the final attempt's logs are in the error: {}", flaky);
+    let quiet_retry = content.lines().find(|l| l.contains("\"stderr_quiet_after_retry_test\"")).unwrap();
+    assert!(quiet_retry.contains("\"status\":\"flaky\""), "logs of a failed attempt don't fail the retry: {}", quiet_retry);
+    let noisy = content.lines().find(|l| l.contains("\"stderr_noisy_test\"")).unwrap();
+    assert!(noisy.contains("\"status\":\"failed\""), "{}", noisy);
+    assert!(noisy.contains("passed but wrote to stderr (allow with ctx.allow_stderr()):\\n[ERROR app_under_test] could not reach cache, falling back"), "{}", noisy);
+    for name in ["stderr_allowed_test", "stderr_allowed_timeout_test", "stderr_quiet_test"] {
+        let line = content.lines().find(|l| l.contains(&format!("\"{}\"", name))).unwrap();
+        assert!(line.contains("\"status\":\"passed\""), "{}", line);
+    }
+    
+    // The logs of each attempt are kept, under a line naming the attempt
+    let html = fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("--- attempt 1 of 2 ---\n[WARN app_under_test] first attempt lost the connection"), "{}", html);
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_panic_formatter_describes_custom_payloads() {
+    // Test that panic_formatter turns non-string panic payloads into readable messages
+    use rust_test_harness::{default_panic_message, PanicFormatter};
+    
+    struct Mismatch { field: &'static str }
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let stream_path = format!("{}/test-reports/test_panic_formatter.ndjson", target_dir);
+    
+    let run = |panic_formatter: Option<PanicFormatter>| {
+        test("panics_with_struct", |_| std::panic::panic_any(Mismatch { field: "total" }));
+        test("panics_with_str", |_| panic!("plain message"));
+        let config = TestConfig {
+            ndjson_stream: Some(stream_path.clone().into()),
+            panic_formatter,
+            ..Default::default()
+        };
+        assert_eq!(run_tests_with_config(config), 1);
+        fs::read_to_string(&stream_path).unwrap()
+    };
+    
+    let content = run(None);
+    assert!(content.contains("\"error\":\"panicked: unknown panic\\n  at tests/reporting_tests.rs:"), "{}", content);
+    
+    let content = run(Some(PanicFormatter::new(|payload| match payload.downcast_ref::<Mismatch>() {
+        Some(mismatch) => format!("mismatch in {}", mismatch.field),
+        None => default_panic_message(payload),
+    })));
+    assert!(content.contains("\"error\":\"panicked: mismatch in total\\n  at tests/reporting_tests.rs:"), "{}", content);
+    assert!(content.contains("\"error\":\"panicked: plain message\\n  at tests/reporting_tests.rs:"), "String payloads still fall back: {}", content);
+    
+    // Cleanup
+    let _ = fs::remove_file(&stream_path);
+}
+
+#[test]
+fn test_report_redactor_scrubs_reports() {
+    // Test that report_redactor is applied to errors in both the HTML report and the NDJSON stream
+    use rust_test_harness::ReportRedactor;
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_report_redactor.html", target_dir);
+    let stream_path = format!("{}/test-reports/test_report_redactor.ndjson", target_dir);
+    
+    test("leaks_connection_string", |_| Err("connect to postgres://app:hunter2@db:5432/app failed".into()));
+    test("leaks_token", |_| Err("401 with Authorization: Bearer abc.def.ghi, api_key=k-123".into()));
+    let config = TestConfig {
+        html_report: Some(html_path.clone()),
+        ndjson_stream: Some(stream_path.clone().into()),
+        report_redactor: Some(ReportRedactor::common_secrets()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    for content in [fs::read_to_string(&html_path).unwrap(), fs::read_to_string(&stream_path).unwrap()] {
+        assert!(!content.contains("hunter2"), "{}", content);
+        assert!(!content.contains("abc.def.ghi"), "{}", content);
+        assert!(!content.contains("k-123"), "{}", content);
+        assert!(content.contains("postgres://app:***@db:5432/app"), "{}", content);
+        assert!(content.contains("Bearer ***"), "{}", content);
+    }
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+    let _ = fs::remove_file(&stream_path);
+}
+
+#[test]
+fn test_common_secrets_redactor_patterns() {
+    // Test the built-in redactor on the patterns it covers, and that it leaves the rest alone
+    use rust_test_harness::ReportRedactor;
+    
+    let redactor = ReportRedactor::common_secrets();
+    assert_eq!(redactor.redact("password=s3cret&user=bob"), "password=***&user=bob");
+    assert_eq!(redactor.redact("DB_PASSWORD: s3cret"), "DB_PASSWORD: ***");
+    assert_eq!(redactor.redact(r#"{"client_secret": "s3cret", "id": 7}"#), r#"{"client_secret": "***", "id": 7}"#);
+    assert_eq!(redactor.redact("authorization: bearer xyz"), "authorization: bearer ***");
+    assert_eq!(redactor.redact("redis://:s3cret@cache:6379"), "redis://:***@cache:6379");
+    assert_eq!(redactor.redact("expected 3, got 4 at localhost:8080"), "expected 3, got 4 at localhost:8080");
+    
+    let custom = ReportRedactor::new(|text| text.replace("internal.example", "<host>"));
+    assert_eq!(custom.redact("timeout reaching internal.example"), "timeout reaching <host>");
+}
+
+#[test]
+fn test_junit_report_generation() {
+    // Test that junit_report writes a testsuite with a testcase per test
+    use rust_test_harness::{test_with_timeout, skip};
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let junit_path = format!("{}/test-reports/test_junit_report.xml", target_dir);
+    
+    test("junit_passes", |_| Ok(()));
+    test("junit_fails", |_| Err("expected <1> & got \"2\"".into()));
+    test("junit_skips", |_| skip!("no database"));
+    test_with_timeout("junit_times_out", Duration::from_millis(50), |_| {
+        std::thread::sleep(Duration::from_millis(300));
+        Ok(())
+    });
+    let config = TestConfig {
+        junit_report: Some("test_junit_report.xml".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    let content = fs::read_to_string(&junit_path).expect("JUnit report should be written to target/test-reports");
+    assert!(content.starts_with("<?xml"), "{}", content);
+    assert!(content.contains(r#"tests="4" failures="2" errors="0" skipped="1""#), "{}", content);
+    assert!(content.contains(r#"<testcase name="junit_passes" time=""#), "{}", content);
+    assert!(content.contains(r#"<failure message="expected &lt;1&gt; &amp; got &quot;2&quot;">"#), "{}", content);
+    assert!(content.contains(r#"<skipped message="no database"/>"#), "{}", content);
+    assert!(content.contains(r#"<failure message="timeout after 50ms">"#), "{}", content);
+    assert_eq!(content.matches("<testcase ").count(), 4);
+    
+    // Cleanup
+    let _ = fs::remove_file(&junit_path);
+}
+
+#[test]
+fn test_html_report_shows_test_durations() {
+    // Test that each test's duration is listed in its metadata
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_durations_report.html", target_dir);
+    
+    test("sleeps_briefly", |_| {
+        std::thread::sleep(Duration::from_millis(20));
+        Ok(())
+    });
+    let config = TestConfig {
+        html_report: Some("test_durations_report.html".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+    
+    let html_content = fs::read_to_string(&html_path).unwrap();
+    let label = r#"<div class="metadata-label">Duration</div><div class="metadata-value">"#;
+    let start = html_content.find(label).expect("HTML should list the test's duration") + label.len();
+    let value = &html_content[start..start + html_content[start..].find('<').unwrap()];
+    assert!(value.ends_with("ms"), "Duration should be formatted like {{:?}}: {}", value);
+    
+    // Cleanup
+    let _ = fs::remove_file(&html_path);
+}
+
+#[cfg(not(feature = "serde_json"))]
+#[test]
+fn test_json_report_without_serde_json_is_invalid_config() {
+    use rust_test_harness::{run_tests_collect, RunOutcome, SharedCounter};
+    
+    let ran = SharedCounter::new();
+    let in_test = ran.clone();
+    test("json_report_unsupported", move |_| {
+        in_test.increment();
+        Ok(())
+    });
+    let report = run_tests_collect(TestConfig {
+        json_report: Some("test_json_unsupported.json".to_string()),
+        ..Default::default()
+    });
+    match report.outcome {
+        RunOutcome::InvalidConfig(problem) => assert_eq!(problem, "json_report \"test_json_unsupported.json\" needs the serde_json feature"),
+        other => panic!("expected InvalidConfig, got {:?}", other),
+    }
+    assert_eq!(ran.get(), 0, "no test runs with an invalid config");
+}
+
+#[cfg(not(feature = "serde_json"))]
+#[test]
+fn test_json_report_env_var_without_serde_json_is_ignored() {
+    // A TEST_JSON_REPORT left in the environment doesn't stop the run the way a configured json_report does
+    
+    std::env::set_var("TEST_JSON_REPORT", "test_json_env_unsupported.json");
+    let config = TestConfig::default();
+    std::env::remove_var("TEST_JSON_REPORT");
+    assert_eq!(config.json_report, None);
+    
+    test("json_env_unsupported", |_| Ok(()));
+    assert_eq!(run_tests_with_config(config), 0);
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json_report_schema() {
+    // Test that json_report writes a versioned summary plus one object per test
+    use rust_test_harness::{test_with_timeout, JSON_REPORT_SCHEMA_VERSION};
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let json_path = format!("{}/test-reports/test_json_report.json", target_dir);
+    
+    test_with_tags("json_passes", vec!["fast"], |_| Ok(()));
+    test("json_fails", |_| Err("wrong answer".into()));
+    test("json_panics", |_| panic!("boom"));
+    test_with_timeout("json_times_out", Duration::from_millis(50), |_| {
+        std::thread::sleep(Duration::from_millis(300));
+        Ok(())
+    });
+    let config = TestConfig {
+        json_report: Some("test_json_report.json".to_string()),
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    
+    let content = fs::read_to_string(&json_path).expect("JSON report should be written to target/test-reports");
+    let report: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(report["schema_version"], JSON_REPORT_SCHEMA_VERSION);
+    assert_eq!(report["summary"]["total"], 4);
+    assert_eq!(report["summary"]["passed"], 1);
+    assert_eq!(report["summary"]["failed"], 3);
+    
+    let tests = report["tests"].as_array().unwrap();
+    let by_name = |name: &str| tests.iter().find(|t| t["name"] == name).unwrap().clone();
+    let passed = by_name("json_passes");
+    assert_eq!(passed["status"], "passed");
+    assert_eq!(passed["tags"], serde_json::json!(["fast"]));
+    assert!(passed["error"].is_null());
+    assert!(passed["duration_ms"].is_u64());
+    assert_eq!(by_name("json_fails")["status"], "failed");
+    assert_eq!(by_name("json_fails")["error"], "wrong answer");
+    assert_eq!(by_name("json_panics")["status"], "panicked");
+    let panic_error = by_name("json_panics")["error"].as_str().unwrap().to_string();
+    assert!(panic_error.starts_with("panicked: boom\n  at tests/reporting_tests.rs:"), "{}", panic_error);
+    assert_eq!(by_name("json_times_out")["status"], "timeout");
+    assert_eq!(by_name("json_times_out")["error"], "timeout after 50ms");
+    
+    // Cleanup
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn test_attachments_in_reports() {
+    // Values attached while a test runs show up in its HTML details and JSON entry
+    use rust_test_harness::{run_tests_collect, test_with_timeout};
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_attachments.html", target_dir);
+    let json_path = format!("{}/test-reports/test_attachments.json", target_dir);
+    
+    test("attaches_request_id", |ctx| {
+        ctx.attach("request_id", "req-42".to_string());
+        ctx.attach("generated_seed", "1234".to_string());
+        Err("upstream returned 502".into())
+    });
+    test_with_timeout("attaches_in_timed_test", Duration::from_secs(5), |ctx| {
+        ctx.attach("shard", "eu-west".to_string());
+        Ok(())
+    });
+    test_with_timeout("attaches_then_times_out", Duration::from_millis(50), |ctx| {
+        ctx.attach("stuck_on", "slow-upstream".to_string());
+        std::thread::sleep(Duration::from_millis(300));
+        Ok(())
+    });
+    let config = TestConfig {
+        html_report: Some("test_attachments.html".to_string()),
+        #[cfg(feature = "serde_json")]
+        json_report: Some("test_attachments.json".to_string()),
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    let report = run_tests_collect(config);
+    assert_eq!(report.failed, 2);
+    
+    let html = fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("📎 request_id") && html.contains("req-42"), "request id missing from the HTML report");
+    assert!(html.contains("📎 shard") && html.contains("eu-west"), "a timed test's attachment is missing from the HTML report");
+    assert!(html.contains("📎 stuck_on") && html.contains("slow-upstream"), "a timed-out test's attachment is missing from the HTML report");
+    
+    #[cfg(feature = "serde_json")]
+    {
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        let tests = report["tests"].as_array().unwrap();
+        let attached = tests.iter().find(|t| t["name"] == "attaches_request_id").unwrap();
+        assert_eq!(attached["attachments"], serde_json::json!({ "generated_seed": "1234", "request_id": "req-42" }));
+    }
+    
+    let _ = fs::remove_file(&html_path);
+    let _ = fs::remove_file(&json_path);
+}
+
+#[test]
+fn test_report_order_sorts_html_independently_of_execution() {
+    // report_order only changes how results are listed, not how tests run
+    use rust_test_harness::{ReportOrder, SharedLog, TestCase, TestStatus};
+    
+    let mut tests = vec![TestCase::named("b_slow", &[]), TestCase::named("c_failed", &[]), TestCase::named("a_fast", &[])];
+    tests[0].duration = Some(Duration::from_millis(300));
+    tests[1].status = TestStatus::Failed("broken".into());
+    tests[1].duration = Some(Duration::from_millis(10));
+    tests[2].duration = Some(Duration::from_millis(1));
+    let names = |order: ReportOrder| order.sorted(&tests).iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(ReportOrder::Registration), vec!["b_slow", "c_failed", "a_fast"]);
+    assert_eq!(names(ReportOrder::Alphabetical), vec!["a_fast", "b_slow", "c_failed"]);
+    assert_eq!(names(ReportOrder::SlowestFirst), vec!["b_slow", "c_failed", "a_fast"]);
+    assert_eq!(names(ReportOrder::FailuresFirst), vec!["c_failed", "b_slow", "a_fast"]);
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_report_order.html", target_dir);
+    let ran = SharedLog::new();
+    for name in ["order_passes", "order_fails", "order_also_passes"] {
+        let log = ran.clone();
+        test(name, move |_| {
+            log.push(name);
+            if name == "order_fails" {
+                return Err("broken".into());
+            }
+            Ok(())
+        });
+    }
+    let config = TestConfig {
+        html_report: Some("test_report_order.html".to_string()),
+        report_order: ReportOrder::FailuresFirst,
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+    assert_eq!(ran.entries(), vec!["order_passes", "order_fails", "order_also_passes"]);
+    
+    let html = fs::read_to_string(&html_path).unwrap();
+    let position = |name: &str| html.find(&format!(">{}<", name)).unwrap_or_else(|| panic!("{} missing from the report", name));
+    assert!(position("order_fails") < position("order_passes"));
+    assert!(position("order_passes") < position("order_also_passes"));
+    let _ = fs::remove_file(&html_path);
+}
+
+#[test]
+fn test_ensure_eq_reports_structured_assertions() {
+    // ensure_eq! fails with both values instead of a panic, and the HTML report shows them apart
+    use rust_test_harness::{ensure_eq, run_tests_collect, TestError, TestStatus};
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let html_path = format!("{}/test-reports/test_ensure_eq.html", target_dir);
+    
+    test("ensure_eq_mismatch", |_| {
+        let items = ["apple", "pear"];
+        ensure_eq!(items.len(), 3);
+        Ok(())
+    });
+    test("ensure_eq_match", |_| {
+        ensure_eq!(2 + 2, 4);
+        Ok(())
+    });
+    test("assert_eq_still_panics", |_| {
+        assert_eq!(1, 2);
+        Ok(())
+    });
+    let config = TestConfig {
+        html_report: Some("test_ensure_eq.html".to_string()),
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    let report = run_tests_collect(config);
+    
+    let status = |name: &str| report.results.iter().find(|(n, _, _)| n == name).unwrap().1.clone();
+    let mismatch = TestError::Assertion {
+        left: "2".to_string(),
+        right: "3".to_string(),
+        expr: "items.len() == 3".to_string(),
+    };
+    assert_eq!(mismatch.to_string(), "assertion failed: items.len() == 3\n  left: 2\n right: 3");
+    assert_eq!(status("ensure_eq_mismatch"), TestStatus::Failed(mismatch));
+    assert_eq!(status("ensure_eq_match"), TestStatus::Passed);
+    assert!(matches!(status("assert_eq_still_panics"), TestStatus::Failed(TestError::Panicked(_))));
+    
+    let html = fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("<strong>Assertion failed:</strong> items.len() == 3"), "assertion expression missing from the HTML report");
+    assert!(html.contains(r#"<div class="assertion-left"><strong>left:</strong><pre>2</pre></div><div class="assertion-right"><strong>right:</strong><pre>3</pre></div>"#), "left/right values missing from the HTML report");
+    
+    let _ = fs::remove_file(&html_path);
+}
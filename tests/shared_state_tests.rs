@@ -0,0 +1,70 @@
+//! Tests for the shared counter/log helpers and process-global shared state
+
+use rust_test_harness::{
+    test, before_each, after_each, run_tests_with_config, shared::shared, SharedCounter, SharedLog, TestConfig
+};
+
+#[test]
+fn test_shared_log_records_hook_order() {
+    println!("🧪 Testing SharedLog and SharedCounter...");
+
+    let order = SharedLog::new();
+    let runs = SharedCounter::new();
+
+    let log = order.clone();
+    before_each(move |_| {
+        log.push("before_each");
+        Ok(())
+    });
+    let log = order.clone();
+    after_each(move |_| {
+        log.push("after_each");
+        Ok(())
+    });
+    let (log, counter) = (order.clone(), runs.clone());
+    test("shared_log_test", move |_| {
+        log.push("test");
+        counter.increment();
+        Ok(())
+    });
+
+    let config = TestConfig {
+        max_concurrency: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+
+    assert_eq!(order.entries(), vec!["before_each", "test", "after_each"]);
+    assert_eq!(runs.get(), 1);
+    assert_eq!(runs.add(2), 3);
+
+    println!("✅ SharedLog and SharedCounter test passed");
+}
+
+#[test]
+fn test_shared_state_by_key() {
+    println!("🧪 Testing keyed shared state...");
+
+    for name in ["shared_state_a", "shared_state_b"] {
+        test(name, move |ctx| {
+            ctx.shared::<Vec<String>>("shared_state_visits").lock().unwrap().push(name.to_string());
+            Ok(())
+        });
+    }
+
+    let config = TestConfig {
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+
+    let mut visits = shared::<Vec<String>>("shared_state_visits").lock().unwrap().clone();
+    visits.sort();
+    assert_eq!(visits, vec!["shared_state_a", "shared_state_b"]);
+
+    // A key is bound to the type it was created with
+    let mismatch = std::panic::catch_unwind(|| shared::<u64>("shared_state_visits"));
+    assert!(mismatch.is_err());
+
+    println!("✅ Keyed shared state test passed");
+}
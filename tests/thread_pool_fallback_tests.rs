@@ -0,0 +1,28 @@
+//! Tests for falling back to sequential execution when the thread pool can't be created
+
+use rust_test_harness::{test, run_tests_with_config, fail_next_thread_pool_build, SharedLog, TestConfig};
+
+#[test]
+fn test_pool_build_failure_falls_back_to_sequential() {
+    println!("🧪 Testing sequential fallback...");
+    
+    let ran = SharedLog::new();
+    for name in ["fallback_a", "fallback_b", "fallback_c"] {
+        let ran = ran.clone();
+        test(name, move |ctx| {
+            assert!(!ctx.is_parallel(), "Tests should run one at a time after the fallback");
+            ran.push(name);
+            Ok(())
+        });
+    }
+    
+    fail_next_thread_pool_build();
+    let config = TestConfig {
+        max_concurrency: Some(4),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 0);
+    assert_eq!(ran.entries(), vec!["fallback_a", "fallback_b", "fallback_c"]);
+    
+    println!("✅ Sequential fallback test passed");
+}
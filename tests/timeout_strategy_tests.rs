@@ -8,9 +8,10 @@
 //! 5. Error handling and reporting
 
 use rust_test_harness::{
-    test_with_timeout, run_tests_with_config, TestConfig, TimeoutConfig, TimeoutStrategy,
-    clear_test_registry, clear_global_context, TestError
+    test_with_timeout, run_tests_with_config, after_each, TestConfig, TimeoutConfig, TimeoutStrategy,
+    clear_test_registry, clear_global_context, TestError, Timeout
 };
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 #[test]
@@ -438,4 +439,198 @@ fn test_timeout_strategy_workflow() {
     }
     
     println!("✅ Complete timeout strategy workflow test passed");
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_timeout_error_includes_last_checkpoint() {
+    println!("🧪 Testing checkpoints on timed-out tests...");
+    
+    clear_test_registry();
+    
+    let stream_path = std::env::temp_dir().join(format!("checkpoint_timeout_{}.ndjson", std::process::id()));
+    let config = TestConfig {
+        skip_hooks: Some(true),
+        max_concurrency: Some(1),
+        ndjson_stream: Some(stream_path.clone()),
+        ..Default::default()
+    };
+    
+    test_with_timeout("checkpointed_timeout_test", Duration::from_millis(100), |ctx| {
+        ctx.checkpoint("connected");
+        ctx.checkpoint("query_sent");
+        std::thread::sleep(Duration::from_millis(500));
+        ctx.checkpoint("query_done");
+        Ok(())
+    });
+    
+    let result = run_tests_with_config(config);
+    assert_eq!(result, 1, "Timed-out test should fail");
+    
+    let stream = std::fs::read_to_string(&stream_path).unwrap();
+    assert!(stream.contains("last checkpoint: 'query_sent'"), "unexpected stream: {}", stream);
+    
+    // The checkpoint travels with the error itself
+    let error = TestError::TimeoutAtCheckpoint { timeout: Duration::from_secs(1), checkpoint: "connected".to_string() };
+    assert_eq!(error.to_string(), "timeout after 1s (last checkpoint: 'connected')");
+    
+    let _ = std::fs::remove_file(&stream_path);
+    
+    println!("✅ Checkpoint timeout test passed");
+}
+
+#[test]
+fn test_relative_timeout_uses_recorded_baseline() {
+    println!("🧪 Testing relative timeouts...");
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let timings_path = std::path::PathBuf::from(format!("{}/test-reports/test_relative_timeouts.json", target_dir));
+    let _ = std::fs::remove_file(&timings_path);
+    let config = || TestConfig {
+        timings_file: timings_path.clone(),
+        skip_hooks: Some(true),
+        ..Default::default()
+    };
+    
+    // First run: no baseline yet, so nothing is enforced and the duration is recorded
+    test_with_timeout("relative_timeout_test", Timeout::Relative(3.0), |_ctx| {
+        std::thread::sleep(Duration::from_millis(50));
+        Ok(())
+    });
+    assert_eq!(run_tests_with_config(config()), 0);
+    let timings = std::fs::read_to_string(&timings_path).unwrap();
+    assert!(timings.contains("\"relative_timeout_test\": "), "baseline should be recorded: {}", timings);
+    
+    // Within three times the baseline still passes, and the baseline is kept
+    test_with_timeout("relative_timeout_test", Timeout::Relative(3.0), |_ctx| {
+        std::thread::sleep(Duration::from_millis(60));
+        Ok(())
+    });
+    assert_eq!(run_tests_with_config(config()), 0);
+    assert_eq!(std::fs::read_to_string(&timings_path).unwrap(), timings);
+    
+    // Well past three times the baseline times out
+    test_with_timeout("relative_timeout_test", Timeout::Relative(3.0), |_ctx| {
+        std::thread::sleep(Duration::from_millis(600));
+        Ok(())
+    });
+    assert_eq!(run_tests_with_config(config()), 1);
+    
+    // Cleanup
+    let _ = std::fs::remove_file(&timings_path);
+    
+    println!("✅ Relative timeout test passed");
+}
+
+#[test]
+fn test_relative_timeout_rejects_bad_factors() {
+    use rust_test_harness::{run_tests_collect, RunOutcome};
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let timings_path = std::path::PathBuf::from(format!("{}/test-reports/test_bad_factors.json", target_dir));
+    std::fs::create_dir_all(timings_path.parent().unwrap()).unwrap();
+    std::fs::write(&timings_path, r#"{"huge_factor": 20}"#).unwrap();
+    let config = || TestConfig {
+        timings_file: timings_path.clone(),
+        skip_hooks: Some(true),
+        ..Default::default()
+    };
+    
+    for factor in [f64::NAN, f64::INFINITY, -1.0] {
+        test_with_timeout("bad_factor", Timeout::Relative(factor), |_ctx| Ok(()));
+        match run_tests_collect(config()).outcome {
+            RunOutcome::InvalidConfig(problem) => assert!(problem.starts_with("test 'bad_factor' has relative timeout factor"), "{}", problem),
+            other => panic!("expected InvalidConfig for factor {}, got {:?}", factor, other),
+        }
+    }
+    
+    // A factor too large for a Duration means no timeout rather than a panic
+    test_with_timeout("huge_factor", Timeout::Relative(1e300), |_ctx| Ok(()));
+    assert_eq!(run_tests_with_config(config()), 0);
+    
+    let _ = std::fs::remove_file(&timings_path);
+}
+
+#[test]
+fn test_relative_timeout_reads_any_timings_layout() {
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let timings_path = std::path::PathBuf::from(format!("{}/test-reports/test_timings_layout.json", target_dir));
+    std::fs::create_dir_all(timings_path.parent().unwrap()).unwrap();
+    let config = || TestConfig {
+        timings_file: timings_path.clone(),
+        skip_hooks: Some(true),
+        ..Default::default()
+    };
+    
+    // Hand-edited onto one line, with an escaped name: the 20ms baselines are still enforced
+    std::fs::write(&timings_path, r#"{"layout \"quoted\"": 20, "layout_other": 20}"#).unwrap();
+    test_with_timeout("layout \"quoted\"", Timeout::Relative(2.0), |_ctx| {
+        std::thread::sleep(Duration::from_millis(500));
+        Ok(())
+    });
+    assert_eq!(run_tests_with_config(config()), 1);
+    
+    // A file that isn't a timings object is reported and left alone, not overwritten
+    std::fs::write(&timings_path, "not timings\n").unwrap();
+    test_with_timeout("layout_unreadable", Timeout::Relative(2.0), |_ctx| Ok(()));
+    assert_eq!(run_tests_with_config(config()), 0);
+    assert_eq!(std::fs::read_to_string(&timings_path).unwrap(), "not timings\n");
+    
+    let _ = std::fs::remove_file(&timings_path);
+}
+
+#[test]
+fn test_per_test_timeout_strategy_overrides_config() {
+    // A Graceful cleanup window longer than the timeout lets a slow finish through; the
+    // per-test strategy should win over the config's, and plain timeouts keep the config's
+    use rust_test_harness::test_with_timeout_strategy;
+    
+    let slow_finish = |_: &mut rust_test_harness::TestContext| {
+        std::thread::sleep(Duration::from_millis(150));
+        Ok(())
+    };
+    let config = || TestConfig {
+        timeout_config: TimeoutConfig { strategy: TimeoutStrategy::Aggressive },
+        ..Default::default()
+    };
+    
+    test_with_timeout_strategy("graceful_override", Duration::from_millis(50), TimeoutStrategy::Graceful(Duration::from_millis(500)), slow_finish);
+    assert_eq!(run_tests_with_config(config()), 0, "the per-test Graceful strategy should allow cleanup time");
+    
+    test_with_timeout("config_strategy", Duration::from_millis(50), slow_finish);
+    assert_eq!(run_tests_with_config(config()), 1, "without an override the config's Aggressive strategy applies");
+    
+    test_with_timeout("config_graceful", Duration::from_millis(50), slow_finish);
+    let graceful = TestConfig {
+        timeout_config: TimeoutConfig { strategy: TimeoutStrategy::Graceful(Duration::from_millis(500)) },
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(graceful), 0, "test_with_timeout follows the config's strategy");
+}
+
+#[test]
+fn test_timed_test_data_reaches_outer_context() {
+    // A timed test runs on its own thread; whatever it stores must still be visible to after_each
+    #[derive(Debug, Clone, PartialEq)]
+    struct Session {
+        id: u32,
+        tokens: Vec<String>,
+    }
+    
+    clear_test_registry();
+    let seen = Arc::new(Mutex::new(None));
+    let seen_in_hook = Arc::clone(&seen);
+    after_each(move |ctx| {
+        *seen_in_hook.lock().unwrap() = ctx.get_data::<Session>("session").cloned();
+        Ok(())
+    });
+    test_with_timeout("stores_custom_struct", Duration::from_secs(5), |ctx| {
+        ctx.set_data("session", Session { id: 7, tokens: vec!["a".into(), "b".into()] });
+        Ok(())
+    });
+    
+    assert_eq!(run_tests_with_config(TestConfig::default()), 0);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        Some(Session { id: 7, tokens: vec!["a".into(), "b".into()] })
+    );
+}
@@ -0,0 +1,33 @@
+//! Tests for the worker threads the Simple timeout strategy leaves behind
+//!
+//! These count the process's threads, so they live in their own test binary where no other test
+//! starts or ends threads while they count. The count comes from /proc, so they only run on Linux
+
+#![cfg(target_os = "linux")]
+
+use rust_test_harness::{run_tests_with_config, test_with_timeout, TestConfig, TimeoutConfig, TimeoutStrategy};
+use std::time::Duration;
+
+#[test]
+fn test_simple_strategy_joins_timed_out_workers() {
+    // Under Simple, each overrunning test is waited for, so a suite of them doesn't leave
+    // hundreds of sleeping worker threads behind
+    let thread_count = || std::fs::read_dir("/proc/self/task").unwrap().count();
+    let before = thread_count();
+    
+    for i in 0..100 {
+        test_with_timeout(&format!("simple_overrun_{}", i), Duration::from_millis(5), |_ctx| {
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        });
+    }
+    let config = TestConfig {
+        timeout_config: TimeoutConfig { strategy: TimeoutStrategy::Simple },
+        max_concurrency: Some(10),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1, "overruns are still reported as timeouts");
+    
+    let after = thread_count();
+    assert!(after < before + 50, "{} threads alive after the run, {} before", after, before);
+}
@@ -0,0 +1,140 @@
+//! Tests for the `tracing` span integration
+//!
+//! Installs a global subscriber, so these live in their own test binary.
+//! Run with `cargo test --features tracing`.
+
+#![cfg(feature = "tracing")]
+
+use rust_test_harness::{test, before_all, before_each, run_tests_with_config, TestConfig};
+use std::cell::RefCell;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_core::span::Current;
+use tracing::{Event, Metadata, Subscriber};
+
+struct SpanRecord {
+    name: &'static str,
+    metadata: &'static Metadata<'static>,
+    parent: Option<u64>,
+    fields: Vec<(String, String)>,
+}
+
+impl SpanRecord {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+// Span ids are 1 + the index into this list
+static SPANS: Mutex<Vec<SpanRecord>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static ENTERED: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+struct FieldRecorder<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for FieldRecorder<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_string(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name().to_string(), format!("{:?}", value)));
+    }
+}
+
+/// Minimal subscriber that remembers every span, its parent and its fields
+struct Recorder;
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let parent = if let Some(parent) = attrs.parent() {
+            Some(parent.into_u64())
+        } else if attrs.is_contextual() {
+            ENTERED.with(|entered| entered.borrow().last().copied())
+        } else {
+            None
+        };
+        let mut fields = Vec::new();
+        attrs.record(&mut FieldRecorder(&mut fields));
+        let mut spans = SPANS.lock().unwrap();
+        spans.push(SpanRecord { name: attrs.metadata().name(), metadata: attrs.metadata(), parent, fields });
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = SPANS.lock().unwrap();
+        let record = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut FieldRecorder(&mut record.fields));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        ENTERED.with(|entered| entered.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, _span: &Id) {
+        ENTERED.with(|entered| entered.borrow_mut().pop());
+    }
+
+    // Needed for `Span::current()`, which hands the suite span to worker threads
+    fn current_span(&self) -> Current {
+        match ENTERED.with(|entered| entered.borrow().last().copied()) {
+            Some(id) => Current::new(Id::from_u64(id), SPANS.lock().unwrap()[id as usize - 1].metadata),
+            None => Current::none(),
+        }
+    }
+}
+
+#[test]
+fn test_spans_nest_tests_and_hooks_under_the_suite() {
+    println!("🧪 Testing tracing spans...");
+
+    tracing::subscriber::set_global_default(Recorder).unwrap();
+
+    before_all(|_| Ok(()));
+    before_each(|_| Ok(()));
+    test("traced_passing", |_| Ok(()));
+    test("traced_failing", |_| Err("boom".into()));
+
+    let config = TestConfig {
+        max_concurrency: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(run_tests_with_config(config), 1);
+
+    let spans = SPANS.lock().unwrap();
+    let suite_id = spans.iter().position(|span| span.name == "test_suite").unwrap() as u64 + 1;
+    assert_eq!(spans[suite_id as usize - 1].field("test_count"), Some("2"));
+
+    let before_all_span = spans.iter()
+        .find(|span| span.name == "hook" && span.field("hook.kind") == Some("before_all"))
+        .unwrap();
+    assert_eq!(before_all_span.parent, Some(suite_id));
+
+    for (name, status) in [("traced_passing", "passed"), ("traced_failing", "failed")] {
+        let test_id = spans.iter()
+            .position(|span| span.name == "test" && span.field("test.name") == Some(name))
+            .unwrap() as u64 + 1;
+        let test_span = &spans[test_id as usize - 1];
+        assert_eq!(test_span.parent, Some(suite_id), "{} should run inside the suite span", name);
+        assert_eq!(test_span.field("test.status"), Some(status));
+        assert!(test_span.field("test.duration_ms").is_some());
+
+        // Each test's before_each hook runs inside that test's span
+        assert!(spans.iter().any(|span| {
+            span.name == "hook" && span.field("hook.kind") == Some("before_each") && span.parent == Some(test_id)
+        }));
+    }
+
+    println!("✅ Tracing spans test passed");
+}
@@ -0,0 +1,45 @@
+//! Tests for watch mode
+
+use rust_test_harness::{test, watch_tests, TestConfig};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_watch_reruns_after_a_file_change() {
+    println!("🧪 Testing watch mode...");
+    
+    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
+    let watched_dir = std::path::PathBuf::from(format!("{}/test-watch", target_dir));
+    fs::create_dir_all(&watched_dir).unwrap();
+    
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut registrations = 0;
+    let exit_code = watch_tests(TestConfig::default(), std::slice::from_ref(&watched_dir), || {
+        registrations += 1;
+        if registrations > 2 {
+            return false;
+        }
+        let runs = Arc::clone(&runs);
+        test("watched_test", move |_| {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+        // Touch a watched file once this run is done and the watcher is waiting again
+        let file = watched_dir.join("source.rs");
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(500));
+            fs::write(file, "fn main() {}").unwrap();
+        });
+        true
+    });
+    
+    assert_eq!(exit_code, 0);
+    assert_eq!(runs.load(Ordering::SeqCst), 2, "Each change triggered one rerun");
+    
+    // Cleanup
+    let _ = fs::remove_dir_all(&watched_dir);
+    
+    println!("✅ Watch mode test passed");
+}